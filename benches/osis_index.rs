@@ -0,0 +1,114 @@
+//! Benchmark of looking up verses from an OSIS source, streaming and indexed.
+//!
+//! The OSIS document is synthetic, with every verse of the KJV versification,
+//! so that no Bible text files are needed. Run with `cargo bench`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use raqiya_bible_reference::{parse_reference, Book, OsisSource, Source};
+
+/// Allocator that keeps count of the bytes currently allocated to measure memory use.
+struct CountingAllocator;
+
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of times each indexed lookup is repeated to get a stable average.
+const LOOKUP_ITERATIONS: u32 = 10_000;
+/// References looked up, from the start to the end of the Bible.
+const REFERENCES: [&str; 4] = ["Genesis 1:1", "John 3:16", "Psalms 119", "Revelation 22:21"];
+
+fn main() {
+    let data = synthetic_osis_document();
+    println!("Synthetic OSIS document: {} MB", megabytes(data.len()));
+    let source = OsisSource::from_data(data);
+
+    for reference in REFERENCES {
+        let parse_result = parse_reference(reference).unwrap();
+        let (elapsed, _) = measure(|| source.find_content(&parse_result).unwrap());
+        println!("Streaming lookup of {reference}: {elapsed:?}");
+    }
+
+    let allocated_before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let (elapsed, indexed_source) = measure(|| source.index().unwrap());
+    let allocated_after = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    println!(
+        "Indexing {} verses: {elapsed:?}, {} MB in memory",
+        indexed_source.index().len(),
+        megabytes(allocated_after.saturating_sub(allocated_before))
+    );
+
+    for reference in REFERENCES {
+        let parse_result = parse_reference(reference).unwrap();
+        let (elapsed, _) = measure(|| {
+            for _ in 0..LOOKUP_ITERATIONS {
+                black_box(
+                    indexed_source
+                        .find_content(black_box(&parse_result))
+                        .unwrap(),
+                );
+            }
+        });
+        println!(
+            "Indexed lookup of {reference}: {:?} on average",
+            elapsed / LOOKUP_ITERATIONS
+        );
+    }
+}
+
+/// Builds an OSIS document with a verse of placeholder text for every verse of every book.
+fn synthetic_osis_document() -> Vec<u8> {
+    let mut document = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+<osisText osisIDWork="Synthetic">
+"#,
+    );
+    for book in Book::ALL {
+        let osis_id = book.osis_id();
+        document.push_str(&format!("<div type=\"book\" osisID=\"{osis_id}\">\n"));
+        for chapter in 1..=book.chapter_count() {
+            document.push_str(&format!("<chapter osisID=\"{osis_id}.{chapter}\">\n<p>\n"));
+            for verse in 1..=book.verse_count(chapter).unwrap() {
+                document.push_str(&format!(
+                    "<verse osisID=\"{osis_id}.{chapter}.{verse}\">And it came to pass in verse {verse} of chapter {chapter}, that <transChange type=\"added\">there was</transChange> text of a typical length in the book.</verse>\n"
+                ));
+            }
+            document.push_str("</p>\n</chapter>\n");
+        }
+        document.push_str("</div>\n");
+    }
+    document.push_str("</osisText>\n</osis>\n");
+    document.into_bytes()
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+fn megabytes(bytes: usize) -> String {
+    format!("{:.1}", bytes as f64 / 1_000_000.0)
+}
@@ -1,6 +1,12 @@
-use std::{env, fs::File};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
-use bible_ref::{OsisSource, Source};
+use bible_ref::{
+    Break, CompiledSource, Heading, Library, Reference, ReferenceParseResult, Segment, Source,
+    VerseContent,
+};
 use raqiya_bible_reference as bible_ref;
 
 fn main() {
@@ -9,41 +15,302 @@ fn main() {
         std::process::exit(1);
     }
 }
+/// ANSI escape code to print text in red.
+const ANSI_RED: &str = "\x1b[31m";
+/// ANSI escape code to reset the text style.
+const ANSI_RESET: &str = "\x1b[0m";
+/// Environment variable with directories of texts, separated like in `PATH`.
+const LIBRARY_PATH_VARIABLE: &str = "RAQIYA_BIBLE_PATH";
+
+/// How verses are laid out when printed.
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    /// Each verse on its own line, prefixed by its reference.
+    VersePerLine,
+    /// Running text in paragraphs and poetic lines as in the source, with verse numbers inline.
+    Paragraphs,
+}
+
 fn run() -> Result<(), String> {
-    let mut args = std::env::args();
-    args.next();
+    let mut red_letter = false;
+    let mut words_of_christ_only = false;
+    let mut include_headings = false;
+    let mut require_known_book = false;
+    let mut layout = Layout::VersePerLine;
+    let mut positional_args = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--red-letter" => red_letter = true,
+            "--words-of-christ" => words_of_christ_only = true,
+            "--headings" => include_headings = true,
+            "--known-books" => require_known_book = true,
+            "--paragraphs" => layout = Layout::Paragraphs,
+            "--verse-per-line" => layout = Layout::VersePerLine,
+            option if option.starts_with("--") => {
+                return Err(format!("Unknown option: {}", option));
+            }
+            _ => positional_args.push(arg),
+        }
+    }
+    let mut args = positional_args.into_iter();
     let Some(text) = args.next() else {
         return Err("No text as command argument #1 given.".into());
     };
+    let library = Library::scan(library_directories());
+    if text == "list" {
+        print_library(&library);
+        return Ok(());
+    }
     let Some(bible_ref) = args
         .next() else {
             return Err("No Bible reference as command argument #2 given.".into());
         };
-    let parse_result = bible_ref::parse_references(&bible_ref)
-        .map_err(|err| err.to_string(bible_ref::Locale::En))?;
-
-    let osis_source_target = match text.as_str() {
-        "KJV" => "assets/kjv.xml",
-        "R1933/-38" => "assets/r1933-38.xml",
-        _ => return Err(format!("Unsupported text: {}", text)),
+    // Book names in other languages are accepted unless known English names are required.
+    let config = bible_ref::ParserConfig {
+        require_known_book,
+        ..bible_ref::ParserConfig::default()
     };
-    let osis_source_path = env::current_dir().unwrap().join(osis_source_target);
-    let file = File::open(&osis_source_path).unwrap();
-    let osis_source = OsisSource::from_file(file);
+    let parse_result = bible_ref::parse_references_with_config(&bible_ref, &config).map_err(|err| {
+        let message = err.to_string(bible_ref::Locale::En);
+        match err.suggestions() {
+            [] => message.to_string(),
+            suggestions => format!(
+                "{} Did you mean {}?",
+                message,
+                suggestions
+                    .iter()
+                    .map(|book| book.name(bible_ref::Locale::En))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    })?;
+
+    // Many texts separated by commas, e.g. "KJV,R1933/-38", are shown verse by verse.
+    let texts = text.split(',').map(str::trim).collect::<Vec<_>>();
+    if texts.len() > 1 {
+        if words_of_christ_only || include_headings || layout == Layout::Paragraphs {
+            return Err(
+                "The options --words-of-christ, --headings and --paragraphs are not supported with many texts."
+                    .into(),
+            );
+        }
+        let sources = texts
+            .iter()
+            .map(|text| open_text(&library, text))
+            .collect::<Result<Vec<_>, _>>()?;
+        return print_parallel(&texts, &sources, &parse_result, red_letter);
+    }
+    let source = open_text(&library, &text)?;
 
     for parsed_reference in parse_result.iter() {
-        let references = osis_source.find_content(parsed_reference)?;
+        let references = if words_of_christ_only {
+            source.find_words_of_christ(parsed_reference)
+        } else {
+            source.look_up(parsed_reference).map(|lookup| {
+                if !lookup.is_complete() {
+                    eprintln!(
+                        "{}",
+                        bible_ref::SourceError::ReferenceNotFound {
+                            missing: lookup.missing
+                        }
+                    );
+                }
+                lookup.references
+            })
+        }
+        .map_err(|err| err.to_string())?;
+        let headings = if include_headings {
+            source.find_headings(parsed_reference).to_vec()
+        } else {
+            vec![]
+        };
 
+        if layout == Layout::Paragraphs {
+            println!("{}", format_paragraphs(&references, &headings, red_letter));
+            continue;
+        }
         for reference in references {
+            for heading in headings_of(&headings, &reference) {
+                println!("{}", heading.text);
+            }
             println!(
                 "{} {}:{} {}",
                 parsed_reference.book_name,
-                parsed_reference.chapter,
+                reference.chapter,
                 reference.number,
-                reference.content
+                format_content(&reference.content, red_letter)
             );
         }
     }
 
     Ok(())
 }
+/// Returns the directories to look for texts in: those of the environment variable,
+/// then `assets` next to the binary and `assets` in the current directory.
+fn library_directories() -> Vec<PathBuf> {
+    let mut directories = env::var_os(LIBRARY_PATH_VARIABLE)
+        .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Some(binary_directory) = env::current_exe()
+        .ok()
+        .as_deref()
+        .and_then(Path::parent)
+    {
+        directories.push(binary_directory.join("assets"));
+    }
+    if let Ok(current_directory) = env::current_dir() {
+        directories.push(current_directory.join("assets"));
+    }
+    directories
+}
+/// Opens an installed text by its abbreviation. The text is compiled on first use and
+/// whenever its file changes, so that later runs don't need to parse it.
+fn open_text(library: &Library, text: &str) -> Result<CompiledSource, String> {
+    let Some(entry) = library.find(text) else {
+        return Err(format!(
+            "Unsupported text: {}. See the installed texts with the command \"list\".",
+            text
+        ));
+    };
+    entry.open().map_err(|err| err.to_string())
+}
+/// Prints each verse followed by its content in each text, marking the verses a text doesn't have,
+/// and warns of the parts of the reference each text doesn't have.
+fn print_parallel(
+    texts: &[&str],
+    sources: &[CompiledSource],
+    parse_result: &[ReferenceParseResult],
+    red_letter: bool,
+) -> Result<(), String> {
+    let label_width = texts
+        .iter()
+        .map(|text| text.chars().count())
+        .max()
+        .unwrap_or_default();
+    for parsed_reference in parse_result.iter() {
+        let lookup =
+            bible_ref::find_parallel(sources, parsed_reference).map_err(|err| err.to_string())?;
+        for (text, missing) in texts.iter().zip(lookup.missing) {
+            if !missing.is_empty() {
+                eprintln!(
+                    "{}: {}",
+                    text,
+                    bible_ref::SourceError::ReferenceNotFound { missing }
+                );
+            }
+        }
+        for verse in lookup.verses {
+            println!(
+                "{} {}:{}",
+                parsed_reference.book_name, verse.chapter, verse.number
+            );
+            for (text, content) in texts.iter().zip(&verse.contents) {
+                let content = match content {
+                    Some(content) => format_content(content, red_letter),
+                    None => "(missing)".to_string(),
+                };
+                println!("  {:<width$}  {}", text, content, width = label_width);
+            }
+        }
+    }
+    Ok(())
+}
+/// Prints the abbreviation of each installed text, with its title and language if known,
+/// and warns of the files that couldn't be read.
+fn print_library(library: &Library) {
+    for err in library.skipped() {
+        eprintln!("Skipped: {}", err);
+    }
+    for entry in library.entries() {
+        let mut line = format!("{:<12}", entry.abbreviation());
+        if let Some(title) = &entry.metadata.title {
+            line.push_str(&format!(" {}", title));
+        }
+        if let Some(language) = &entry.metadata.language {
+            line.push_str(&format!(" ({})", language));
+        }
+        println!("{}", line.trim_end());
+    }
+}
+/// Returns the headings that stand before the given verse.
+fn headings_of<'a>(
+    headings: &'a [Heading],
+    reference: &Reference,
+) -> impl Iterator<Item = &'a Heading> {
+    let (chapter, number) = (reference.chapter, reference.number);
+    headings.iter().filter(move |heading| {
+        (heading.verse_id.chapter(), heading.verse_id.verse()) == (chapter, number)
+    })
+}
+/// Formats verse content as plain text, printing words of Christ in red if `red_letter` is set.
+fn format_content(content: &VerseContent, red_letter: bool) -> String {
+    let mut formatted = String::new();
+    for segment in content.segments() {
+        push_segment_text(&mut formatted, segment, red_letter);
+    }
+    formatted
+}
+/// Formats verses as running text, starting paragraphs and poetic lines where the source does.
+/// Verse numbers are inline, and the chapter is included with the first verse of each chapter.
+fn format_paragraphs(references: &[Reference], headings: &[Heading], red_letter: bool) -> String {
+    let mut formatted = String::new();
+    let mut previous_chapter = None;
+    for reference in references {
+        for heading in headings_of(headings, reference) {
+            push_paragraph_break(&mut formatted);
+            formatted.push_str(&heading.text);
+            push_paragraph_break(&mut formatted);
+        }
+        let mut is_number_written = false;
+        for segment in reference.content.segments() {
+            if let Segment::Break(layout_break) = segment {
+                match layout_break {
+                    Break::Paragraph | Break::Stanza => push_paragraph_break(&mut formatted),
+                    Break::Line { indent } => {
+                        push_line_break(&mut formatted);
+                        formatted.push_str(&"  ".repeat(*indent as usize));
+                    }
+                }
+                continue;
+            }
+            if !is_number_written {
+                if !formatted.is_empty() && !formatted.ends_with(char::is_whitespace) {
+                    formatted.push(' ');
+                }
+                if previous_chapter != Some(reference.chapter) {
+                    formatted.push_str(&format!("[{}:{}] ", reference.chapter, reference.number));
+                } else {
+                    formatted.push_str(&format!("[{}] ", reference.number));
+                }
+                previous_chapter = Some(reference.chapter);
+                is_number_written = true;
+            }
+            push_segment_text(&mut formatted, segment, red_letter);
+        }
+    }
+    formatted.trim_end().to_string()
+}
+fn push_paragraph_break(formatted: &mut String) {
+    formatted.truncate(formatted.trim_end().len());
+    if !formatted.is_empty() {
+        formatted.push_str("\n\n");
+    }
+}
+fn push_line_break(formatted: &mut String) {
+    formatted.truncate(formatted.trim_end_matches(' ').len());
+    if !formatted.is_empty() && !formatted.ends_with('\n') {
+        formatted.push('\n');
+    }
+}
+fn push_segment_text(formatted: &mut String, segment: &Segment, red_letter: bool) {
+    match (segment.text(), segment.style()) {
+        (Some(text), Some(style)) if red_letter && style.words_of_christ => {
+            formatted.push_str(ANSI_RED);
+            formatted.push_str(text);
+            formatted.push_str(ANSI_RESET);
+        }
+        (Some(text), _) => formatted.push_str(text),
+        _ => {}
+    }
+}
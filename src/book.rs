@@ -0,0 +1,707 @@
+use crate::{versification, Locale};
+
+/// Book of the Bible in the Protestant canon.
+///
+/// The discriminant of each book is its number in canonical order, starting from 1 (Genesis).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Book {
+    Genesis = 1,
+    Exodus,
+    Leviticus,
+    Numbers,
+    Deuteronomy,
+    Joshua,
+    Judges,
+    Ruth,
+    FirstSamuel,
+    SecondSamuel,
+    FirstKings,
+    SecondKings,
+    FirstChronicles,
+    SecondChronicles,
+    Ezra,
+    Nehemiah,
+    Esther,
+    Job,
+    Psalms,
+    Proverbs,
+    Ecclesiastes,
+    SongOfSolomon,
+    Isaiah,
+    Jeremiah,
+    Lamentations,
+    Ezekiel,
+    Daniel,
+    Hosea,
+    Joel,
+    Amos,
+    Obadiah,
+    Jonah,
+    Micah,
+    Nahum,
+    Habakkuk,
+    Zephaniah,
+    Haggai,
+    Zechariah,
+    Malachi,
+    Matthew,
+    Mark,
+    Luke,
+    John,
+    Acts,
+    Romans,
+    FirstCorinthians,
+    SecondCorinthians,
+    Galatians,
+    Ephesians,
+    Philippians,
+    Colossians,
+    FirstThessalonians,
+    SecondThessalonians,
+    FirstTimothy,
+    SecondTimothy,
+    Titus,
+    Philemon,
+    Hebrews,
+    James,
+    FirstPeter,
+    SecondPeter,
+    FirstJohn,
+    SecondJohn,
+    ThirdJohn,
+    Jude,
+    Revelation,
+}
+impl Book {
+    /// All books in canonical order.
+    pub const ALL: [Book; 66] = [
+        Book::Genesis,
+        Book::Exodus,
+        Book::Leviticus,
+        Book::Numbers,
+        Book::Deuteronomy,
+        Book::Joshua,
+        Book::Judges,
+        Book::Ruth,
+        Book::FirstSamuel,
+        Book::SecondSamuel,
+        Book::FirstKings,
+        Book::SecondKings,
+        Book::FirstChronicles,
+        Book::SecondChronicles,
+        Book::Ezra,
+        Book::Nehemiah,
+        Book::Esther,
+        Book::Job,
+        Book::Psalms,
+        Book::Proverbs,
+        Book::Ecclesiastes,
+        Book::SongOfSolomon,
+        Book::Isaiah,
+        Book::Jeremiah,
+        Book::Lamentations,
+        Book::Ezekiel,
+        Book::Daniel,
+        Book::Hosea,
+        Book::Joel,
+        Book::Amos,
+        Book::Obadiah,
+        Book::Jonah,
+        Book::Micah,
+        Book::Nahum,
+        Book::Habakkuk,
+        Book::Zephaniah,
+        Book::Haggai,
+        Book::Zechariah,
+        Book::Malachi,
+        Book::Matthew,
+        Book::Mark,
+        Book::Luke,
+        Book::John,
+        Book::Acts,
+        Book::Romans,
+        Book::FirstCorinthians,
+        Book::SecondCorinthians,
+        Book::Galatians,
+        Book::Ephesians,
+        Book::Philippians,
+        Book::Colossians,
+        Book::FirstThessalonians,
+        Book::SecondThessalonians,
+        Book::FirstTimothy,
+        Book::SecondTimothy,
+        Book::Titus,
+        Book::Philemon,
+        Book::Hebrews,
+        Book::James,
+        Book::FirstPeter,
+        Book::SecondPeter,
+        Book::FirstJohn,
+        Book::SecondJohn,
+        Book::ThirdJohn,
+        Book::Jude,
+        Book::Revelation,
+    ];
+
+    /// Returns the book with the given number in canonical order (1 = Genesis, 66 = Revelation).
+    pub fn from_number(number: u8) -> Option<Book> {
+        Book::ALL.get((number as usize).checked_sub(1)?).copied()
+    }
+    /// Returns the book by its name or abbreviation in the given locale.
+    /// OSIS book IDs are always accepted regardless of the locale.
+    ///
+    /// Matching ignores letter case, whitespace and periods,
+    /// so "1 John", "1john" and "1 Jn." all refer to the same book.
+    pub fn from_name(name: &str, locale: Locale) -> Option<Book> {
+        if let Some(book) = Book::from_osis_id(name) {
+            return Some(book);
+        }
+        let name = normalize_book_name(name);
+        Book::ALL
+            .into_iter()
+            .find(|book| book.aliases(locale).contains(&name.as_str()))
+    }
+    /// Returns the book by its OSIS book ID, e.g. "Gen" or "1John".
+    pub fn from_osis_id(osis_id: &str) -> Option<Book> {
+        Book::ALL.into_iter().find(|book| book.osis_id() == osis_id)
+    }
+    /// Returns the book by its USFM book code, e.g. "GEN" or "1JN", ignoring letter case.
+    /// The same codes are used by USX and Paratext.
+    pub fn from_usfm_id(usfm_id: &str) -> Option<Book> {
+        Book::ALL
+            .into_iter()
+            .find(|book| book.usfm_id().eq_ignore_ascii_case(usfm_id))
+    }
+    /// Returns books whose names or abbreviations in the given locale are close to the given name,
+    /// e.g. John for "Jhon". Useful for "did you mean?" suggestions for unknown book names.
+    ///
+    /// Closeness is measured by edit distance, where swapping two adjacent letters counts as
+    /// a single edit. Suggestions are ranked from the closest to the furthest and are limited
+    /// to [`MAX_BOOK_SUGGESTIONS`] books.
+    pub fn suggestions(name: &str, locale: Locale) -> Vec<Book> {
+        let name = normalize_book_name(name);
+        let name_len = name.chars().count();
+        // Allow roughly one edit per three letters, so that short abbreviations
+        // don't match almost every other short abbreviation.
+        let max_distance = (name_len / 3).max(1);
+
+        let mut suggestions = Book::ALL
+            .into_iter()
+            .filter_map(|book| {
+                book.aliases(locale)
+                    .iter()
+                    .map(|alias| {
+                        let distance = edit_distance(&name, alias);
+                        let len_difference = alias.chars().count().abs_diff(name_len);
+                        (distance, len_difference)
+                    })
+                    .min()
+                    .filter(|(distance, _)| *distance <= max_distance)
+                    .map(|rank| (rank, book))
+            })
+            .collect::<Vec<_>>();
+        // Sorting is stable, so books with equal rank stay in canonical order.
+        suggestions.sort_by_key(|(rank, _)| *rank);
+        suggestions
+            .into_iter()
+            .take(MAX_BOOK_SUGGESTIONS)
+            .map(|(_, book)| book)
+            .collect()
+    }
+
+    /// Returns known names and abbreviations of the book in the given locale.
+    ///
+    /// Aliases are stored in normalized form: lowercase without whitespace or periods.
+    pub fn aliases(self, locale: Locale) -> &'static [&'static str] {
+        match locale {
+            Locale::En => EN_ALIASES[self.index()],
+        }
+    }
+    /// Returns the number of chapters in the book.
+    pub fn chapter_count(self) -> u8 {
+        versification::chapter_count(self)
+    }
+    /// Returns the number of verses in the given chapter of the book
+    /// or `None` if the book has no such chapter.
+    pub fn verse_count(self, chapter: u8) -> Option<u8> {
+        versification::verse_count(self, chapter)
+    }
+    /// Returns the literary genre the book is grouped under.
+    pub fn genre(self) -> Genre {
+        match self.number() {
+            1..=5 => Genre::Law,
+            6..=17 | 44 => Genre::History,
+            18..=22 => Genre::Wisdom,
+            23..=39 => Genre::Prophets,
+            40..=43 => Genre::Gospels,
+            45..=65 => Genre::Epistles,
+            _ => Genre::Apocalyptic,
+        }
+    }
+    /// Returns the position of the book in the order of the given canon, starting from 1,
+    /// or `None` if the canon does not include the book.
+    pub fn position(self, canon: Canon) -> Option<u8> {
+        canon
+            .books()
+            .position(|book| book == self)
+            .map(|index| index as u8 + 1)
+    }
+    /// Returns the testament the book belongs to.
+    pub fn testament(self) -> Testament {
+        if self < Book::Matthew {
+            Testament::Old
+        } else {
+            Testament::New
+        }
+    }
+    /// Returns the full name of the book in the given locale.
+    pub fn name(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => EN_NAMES[self.index()],
+        }
+    }
+    /// Returns the number of the book in canonical order (1 = Genesis, 66 = Revelation).
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+    /// Returns the OSIS book ID, e.g. "Gen" or "1John".
+    pub fn osis_id(self) -> &'static str {
+        OSIS_IDS[self.index()]
+    }
+    /// Returns the USFM book code, e.g. "GEN" or "1JN".
+    pub fn usfm_id(self) -> &'static str {
+        USFM_IDS[self.index()]
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self as usize - 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Testament {
+    Old,
+    New,
+}
+impl Testament {
+    /// Returns an iterator over the books of the testament in canonical order.
+    pub fn books(self) -> impl Iterator<Item = Book> {
+        Book::ALL
+            .into_iter()
+            .filter(move |book| book.testament() == self)
+    }
+    pub fn name(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => match self {
+                Testament::Old => "Old Testament",
+                Testament::New => "New Testament",
+            },
+        }
+    }
+}
+
+/// Literary genre of a book, used for grouping books into sections.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Genre {
+    /// Genesis to Deuteronomy.
+    Law,
+    /// Joshua to Esther, and Acts.
+    History,
+    /// Job to Song of Solomon.
+    Wisdom,
+    /// Isaiah to Malachi.
+    Prophets,
+    /// Matthew to John.
+    Gospels,
+    /// Romans to Jude.
+    Epistles,
+    /// Revelation.
+    Apocalyptic,
+}
+impl Genre {
+    pub const ALL: [Genre; 7] = [
+        Genre::Law,
+        Genre::History,
+        Genre::Wisdom,
+        Genre::Prophets,
+        Genre::Gospels,
+        Genre::Epistles,
+        Genre::Apocalyptic,
+    ];
+
+    /// Returns an iterator over the books of the genre in canonical order.
+    pub fn books(self) -> impl Iterator<Item = Book> {
+        Book::ALL
+            .into_iter()
+            .filter(move |book| book.genre() == self)
+    }
+    pub fn name(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => match self {
+                Genre::Law => "Law",
+                Genre::History => "History",
+                Genre::Wisdom => "Wisdom",
+                Genre::Prophets => "Prophets",
+                Genre::Gospels => "Gospels",
+                Genre::Epistles => "Epistles",
+                Genre::Apocalyptic => "Apocalyptic",
+            },
+        }
+    }
+}
+
+/// Canon that defines which books are included and in what order.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Canon {
+    /// The 66 books of the Protestant canon in the order of English Bibles.
+    Protestant,
+    /// The 39 books of the Hebrew Bible (Tanakh) in the order of Torah, Nevi'im and Ketuvim.
+    Hebrew,
+}
+impl Canon {
+    /// Returns an iterator over the books of the canon in its order.
+    pub fn books(self) -> impl Iterator<Item = Book> {
+        match self {
+            Canon::Protestant => Book::ALL.iter().copied(),
+            Canon::Hebrew => HEBREW_CANON_ORDER.iter().copied(),
+        }
+    }
+}
+
+/// Maximum number of books returned by [`Book::suggestions`].
+pub const MAX_BOOK_SUGGESTIONS: usize = 3;
+
+/// Calculates the edit distance between two strings as the number of single character
+/// insertions, deletions, substitutions and transpositions of adjacent characters
+/// needed to turn one string into another (optimal string alignment distance).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // Distances between prefixes of `a` and `b`, one row per prefix length of `a`.
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Normalizes a book name for alias matching.
+///
+/// The name is lowercased, periods and whitespace are removed
+/// and a leading Roman numeral (e.g. "II Kings") is converted to a digit.
+pub(crate) fn normalize_book_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let name = [("iii ", "3"), ("ii ", "2"), ("i ", "1")]
+        .iter()
+        .find_map(|(numeral, digit)| {
+            name.strip_prefix(numeral)
+                .map(|rest| format!("{digit}{rest}"))
+        })
+        .unwrap_or(name);
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '.')
+        .collect()
+}
+
+const HEBREW_CANON_ORDER: [Book; 39] = [
+    // Torah
+    Book::Genesis,
+    Book::Exodus,
+    Book::Leviticus,
+    Book::Numbers,
+    Book::Deuteronomy,
+    // Nevi'im
+    Book::Joshua,
+    Book::Judges,
+    Book::FirstSamuel,
+    Book::SecondSamuel,
+    Book::FirstKings,
+    Book::SecondKings,
+    Book::Isaiah,
+    Book::Jeremiah,
+    Book::Ezekiel,
+    Book::Hosea,
+    Book::Joel,
+    Book::Amos,
+    Book::Obadiah,
+    Book::Jonah,
+    Book::Micah,
+    Book::Nahum,
+    Book::Habakkuk,
+    Book::Zephaniah,
+    Book::Haggai,
+    Book::Zechariah,
+    Book::Malachi,
+    // Ketuvim
+    Book::Psalms,
+    Book::Proverbs,
+    Book::Job,
+    Book::SongOfSolomon,
+    Book::Ruth,
+    Book::Lamentations,
+    Book::Ecclesiastes,
+    Book::Esther,
+    Book::Daniel,
+    Book::Ezra,
+    Book::Nehemiah,
+    Book::FirstChronicles,
+    Book::SecondChronicles,
+];
+
+const OSIS_IDS: [&str; 66] = [
+    "Gen", "Exod", "Lev", "Num", "Deut", "Josh", "Judg", "Ruth", "1Sam", "2Sam", "1Kgs", "2Kgs",
+    "1Chr", "2Chr", "Ezra", "Neh", "Esth", "Job", "Ps", "Prov", "Eccl", "Song", "Isa", "Jer",
+    "Lam", "Ezek", "Dan", "Hos", "Joel", "Amos", "Obad", "Jonah", "Mic", "Nah", "Hab", "Zeph",
+    "Hag", "Zech", "Mal", "Matt", "Mark", "Luke", "John", "Acts", "Rom", "1Cor", "2Cor", "Gal",
+    "Eph", "Phil", "Col", "1Thess", "2Thess", "1Tim", "2Tim", "Titus", "Phlm", "Heb", "Jas",
+    "1Pet", "2Pet", "1John", "2John", "3John", "Jude", "Rev",
+];
+
+const USFM_IDS: [&str; 66] = [
+    "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "1SA", "2SA", "1KI", "2KI", "1CH",
+    "2CH", "EZR", "NEH", "EST", "JOB", "PSA", "PRO", "ECC", "SNG", "ISA", "JER", "LAM", "EZK",
+    "DAN", "HOS", "JOL", "AMO", "OBA", "JON", "MIC", "NAM", "HAB", "ZEP", "HAG", "ZEC", "MAL",
+    "MAT", "MRK", "LUK", "JHN", "ACT", "ROM", "1CO", "2CO", "GAL", "EPH", "PHP", "COL", "1TH",
+    "2TH", "1TI", "2TI", "TIT", "PHM", "HEB", "JAS", "1PE", "2PE", "1JN", "2JN", "3JN", "JUD",
+    "REV",
+];
+
+const EN_NAMES: [&str; 66] = [
+    "Genesis",
+    "Exodus",
+    "Leviticus",
+    "Numbers",
+    "Deuteronomy",
+    "Joshua",
+    "Judges",
+    "Ruth",
+    "1 Samuel",
+    "2 Samuel",
+    "1 Kings",
+    "2 Kings",
+    "1 Chronicles",
+    "2 Chronicles",
+    "Ezra",
+    "Nehemiah",
+    "Esther",
+    "Job",
+    "Psalms",
+    "Proverbs",
+    "Ecclesiastes",
+    "Song of Solomon",
+    "Isaiah",
+    "Jeremiah",
+    "Lamentations",
+    "Ezekiel",
+    "Daniel",
+    "Hosea",
+    "Joel",
+    "Amos",
+    "Obadiah",
+    "Jonah",
+    "Micah",
+    "Nahum",
+    "Habakkuk",
+    "Zephaniah",
+    "Haggai",
+    "Zechariah",
+    "Malachi",
+    "Matthew",
+    "Mark",
+    "Luke",
+    "John",
+    "Acts",
+    "Romans",
+    "1 Corinthians",
+    "2 Corinthians",
+    "Galatians",
+    "Ephesians",
+    "Philippians",
+    "Colossians",
+    "1 Thessalonians",
+    "2 Thessalonians",
+    "1 Timothy",
+    "2 Timothy",
+    "Titus",
+    "Philemon",
+    "Hebrews",
+    "James",
+    "1 Peter",
+    "2 Peter",
+    "1 John",
+    "2 John",
+    "3 John",
+    "Jude",
+    "Revelation",
+];
+
+const EN_ALIASES: [&[&str]; 66] = [
+    &["genesis", "gen", "ge", "gn"],
+    &["exodus", "exod", "exo", "ex"],
+    &["leviticus", "lev", "le", "lv"],
+    &["numbers", "num", "nu", "nm", "nb"],
+    &["deuteronomy", "deut", "deu", "de", "dt"],
+    &["joshua", "josh", "jos", "jsh"],
+    &["judges", "judg", "jdg", "jdgs", "jg"],
+    &["ruth", "rth", "ru"],
+    &["1samuel", "1sam", "1sa", "1sm"],
+    &["2samuel", "2sam", "2sa", "2sm"],
+    &["1kings", "1kgs", "1ki", "1kg"],
+    &["2kings", "2kgs", "2ki", "2kg"],
+    &["1chronicles", "1chron", "1chr", "1ch"],
+    &["2chronicles", "2chron", "2chr", "2ch"],
+    &["ezra", "ezr"],
+    &["nehemiah", "neh", "ne"],
+    &["esther", "esth", "est", "es"],
+    &["job", "jb"],
+    &["psalms", "psalm", "ps", "psa", "pss", "psm"],
+    &["proverbs", "prov", "pro", "prv", "pr"],
+    &["ecclesiastes", "eccl", "eccles", "ecc", "ec", "qoh"],
+    &[
+        "songofsolomon",
+        "songofsongs",
+        "song",
+        "sos",
+        "canticles",
+        "cant",
+    ],
+    &["isaiah", "isa", "is"],
+    &["jeremiah", "jer", "je", "jr"],
+    &["lamentations", "lam", "la"],
+    &["ezekiel", "ezek", "eze", "ezk"],
+    &["daniel", "dan", "da", "dn"],
+    &["hosea", "hos", "ho"],
+    &["joel", "jl"],
+    &["amos", "am"],
+    &["obadiah", "obad", "oba", "ob"],
+    &["jonah", "jon", "jnh"],
+    &["micah", "mic", "mc"],
+    &["nahum", "nah", "na"],
+    &["habakkuk", "hab", "hb"],
+    &["zephaniah", "zeph", "zep", "zp"],
+    &["haggai", "hag", "hg"],
+    &["zechariah", "zech", "zec", "zc"],
+    &["malachi", "mal", "ml"],
+    &["matthew", "matt", "mat", "mt"],
+    &["mark", "mrk", "mar", "mk", "mr"],
+    &["luke", "luk", "lk"],
+    &["john", "joh", "jhn", "jn"],
+    &["acts", "act", "ac"],
+    &["romans", "rom", "ro", "rm"],
+    &["1corinthians", "1cor", "1co"],
+    &["2corinthians", "2cor", "2co"],
+    &["galatians", "gal", "ga"],
+    &["ephesians", "eph", "ephes"],
+    &["philippians", "phil", "php", "pp"],
+    &["colossians", "col"],
+    &["1thessalonians", "1thess", "1thes", "1th"],
+    &["2thessalonians", "2thess", "2thes", "2th"],
+    &["1timothy", "1tim", "1ti"],
+    &["2timothy", "2tim", "2ti"],
+    &["titus", "tit", "ti"],
+    &["philemon", "philem", "phlm", "phm"],
+    &["hebrews", "heb"],
+    &["james", "jas", "jm"],
+    &["1peter", "1pet", "1pe", "1pt"],
+    &["2peter", "2pet", "2pe", "2pt"],
+    &["1john", "1jn", "1jhn", "1joh"],
+    &["2john", "2jn", "2jhn", "2joh"],
+    &["3john", "3jn", "3jhn", "3joh"],
+    &["jude", "jud", "jd"],
+    &["revelation", "revelations", "rev", "re", "rv"],
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn find_book_by_name() {
+        assert_eq!(Book::from_name("Genesis", Locale::En), Some(Book::Genesis));
+        assert_eq!(Book::from_name("1 John", Locale::En), Some(Book::FirstJohn));
+        assert_eq!(Book::from_name("I Jn.", Locale::En), Some(Book::FirstJohn));
+        assert_eq!(Book::from_name("1John", Locale::En), Some(Book::FirstJohn));
+        assert_eq!(
+            Book::from_name("song of songs", Locale::En),
+            Some(Book::SongOfSolomon)
+        );
+        assert_eq!(Book::from_name("Jhon", Locale::En), None);
+    }
+    #[test]
+    fn get_book_metadata() {
+        assert_eq!(Book::Psalms.testament(), Testament::Old);
+        assert_eq!(Book::Psalms.genre(), Genre::Wisdom);
+        assert_eq!(Book::Psalms.chapter_count(), 150);
+        assert_eq!(Book::Acts.genre(), Genre::History);
+        assert_eq!(Book::Revelation.genre(), Genre::Apocalyptic);
+
+        assert_eq!(Book::Ruth.position(Canon::Protestant), Some(8));
+        assert_eq!(Book::Ruth.position(Canon::Hebrew), Some(31));
+        assert_eq!(Book::SecondChronicles.position(Canon::Hebrew), Some(39));
+        assert_eq!(Book::John.position(Canon::Hebrew), None);
+
+        assert_eq!(Book::SongOfSolomon.usfm_id(), "SNG");
+        assert_eq!(Book::from_usfm_id("1jn"), Some(Book::FirstJohn));
+        assert_eq!(Book::from_usfm_id("TOB"), None);
+    }
+    #[test]
+    fn iterate_books_by_testament_and_genre() {
+        assert_eq!(Testament::Old.books().count(), 39);
+        assert_eq!(Testament::New.books().count(), 27);
+        assert_eq!(
+            Genre::Gospels.books().collect::<Vec<_>>(),
+            [Book::Matthew, Book::Mark, Book::Luke, Book::John]
+        );
+        assert_eq!(
+            Genre::ALL
+                .iter()
+                .map(|genre| genre.books().count())
+                .sum::<usize>(),
+            66
+        );
+    }
+    #[test]
+    fn suggest_books_for_misspelled_names() {
+        assert_eq!(Book::suggestions("Jhon", Locale::En)[0], Book::John);
+        assert_eq!(
+            Book::suggestions("Phillipians", Locale::En),
+            [Book::Philippians]
+        );
+        assert_eq!(Book::suggestions("1 Jhon", Locale::En)[0], Book::FirstJohn);
+        assert_eq!(Book::suggestions("Xyzzy", Locale::En), []);
+    }
+    #[test]
+    fn calculate_edit_distance() {
+        assert_eq!(edit_distance("john", "john"), 0);
+        assert_eq!(edit_distance("jhon", "john"), 1);
+        assert_eq!(edit_distance("phillipians", "philippians"), 2);
+        assert_eq!(edit_distance("", "ruth"), 4);
+    }
+    #[test]
+    fn aliases_are_unique_and_normalized() {
+        let mut seen = HashSet::new();
+        for book in Book::ALL {
+            for alias in book.aliases(Locale::En) {
+                assert_eq!(normalize_book_name(alias), *alias);
+                assert!(seen.insert(*alias), "Duplicate alias: {alias}");
+            }
+        }
+    }
+}
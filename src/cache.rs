@@ -0,0 +1,755 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    index, references_of_verses, Book, Break, Heading, HeadingType, IndexedSource, MorphologyCode,
+    Note, NoteType, OsisSource, Reference, ReferenceParseResult, Segment, Source, SourceError,
+    SourceMetadata, StrongsNumber, TextStyle, VerseContent, VerseId, VerseIdRange, Word,
+};
+
+/// Identifies the file format of a compiled source.
+const MAGIC: &[u8; 4] = b"RQBC";
+/// Version of the file format, which is increased whenever the format changes
+/// so that compiled sources of older versions are rebuilt.
+const FORMAT_VERSION: u32 = 2;
+/// Length of the header: magic, format version, source size, source modification time
+/// in seconds and nanoseconds, verse count and the offset of headings and introductions.
+const HEADER_LEN: usize = 36;
+/// Length of an entry of the verse table: verse ID, content offset and content length.
+const VERSE_ENTRY_LEN: usize = 12;
+/// Number of compiled files written by this process so far,
+/// which makes the names of their temporary files unique.
+static WRITE_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Source of Bible content compiled into a binary file, which is memory-mapped when opened.
+///
+/// Opening a compiled source only reads its headings, introductions and metadata,
+/// and lookups decode only the verses found, so it suits programs that start often,
+/// like the CLI.
+///
+/// The file starts with a header, followed by a table of verse IDs in canonical order with
+/// the positions of their content, which is searched directly in the mapped memory.
+/// The compiled file records the size and the modification time of the file it was compiled
+/// from, so that it is rebuilt when the original changes.
+///
+/// If the compiled file can't be written, e.g. because its directory is read-only,
+/// the compiled source is kept in memory instead and compiled again the next time.
+pub struct CompiledSource {
+    data: CompiledData,
+    verse_count: usize,
+    /// Headings sorted by the verses they stand before.
+    headings: Vec<Heading>,
+    introductions: HashMap<Book, String>,
+    metadata: SourceMetadata,
+}
+impl Source for CompiledSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        let Some(bounds) = parse_result.verse_id_bounds() else {
+            return Ok(vec![]);
+        };
+        let verses = self.find_verses_in_bounds(bounds)?;
+        references_of_verses(parse_result, verses)
+    }
+    fn metadata(&self) -> &SourceMetadata {
+        &self.metadata
+    }
+}
+impl CompiledSource {
+    /// Opens the compiled source of an OSIS file. The OSIS file is compiled first
+    /// if the compiled file doesn't exist or the OSIS file has changed since compiling.
+    pub fn open_osis(osis_path: &Path, compiled_path: &Path) -> Result<Self, SourceError> {
+        Self::open_or_compile(osis_path, compiled_path, || {
+            let data = fs::read(osis_path).map_err(|error| SourceError::io(osis_path, error))?;
+            OsisSource::from_data(data).index()
+        })
+    }
+    /// Opens a compiled source, first compiling it with `compile` if the compiled file
+    /// doesn't exist or the source file has changed since compiling.
+    pub fn open_or_compile(
+        source_path: &Path,
+        compiled_path: &Path,
+        compile: impl FnOnce() -> Result<IndexedSource, SourceError>,
+    ) -> Result<Self, SourceError> {
+        Self::open_or_compile_files(&[source_path], compiled_path, compile)
+    }
+    /// Opens a compiled source like [`Self::open_or_compile`], for a source made up of
+    /// many files, e.g. a directory of book files. The source is compiled again
+    /// if any of the files has changed.
+    pub fn open_or_compile_files(
+        source_paths: &[impl AsRef<Path>],
+        compiled_path: &Path,
+        compile: impl FnOnce() -> Result<IndexedSource, SourceError>,
+    ) -> Result<Self, SourceError> {
+        let stamp = SourceStamp::of_files(source_paths)?;
+        if let Ok(compiled_source) = Self::open(compiled_path) {
+            if compiled_source.source_stamp() == stamp {
+                return Ok(compiled_source);
+            }
+        }
+        let data = Self::encode(&compile()?, stamp);
+        match Self::write(&data, compiled_path) {
+            Ok(()) => Self::open(compiled_path),
+            Err(_) => Self::from_data(CompiledData::InMemory(data), compiled_path),
+        }
+    }
+    /// Opens a compiled source without checking whether it's up to date.
+    pub fn open(compiled_path: &Path) -> Result<Self, SourceError> {
+        let file =
+            File::open(compiled_path).map_err(|error| SourceError::io(compiled_path, error))?;
+        // SAFETY: This crate only ever replaces compiled files by renaming a new file over them,
+        // never modifying them in place, so the mapped memory doesn't change while it's in use.
+        // Nothing prevents another process from truncating or rewriting the file, though,
+        // in which case reading the mapped memory is undefined behavior, e.g. a crash with SIGBUS.
+        // Compiled files are private to the library that writes them, so this is not guarded
+        // against.
+        let mmap =
+            unsafe { Mmap::map(&file) }.map_err(|error| SourceError::io(compiled_path, error))?;
+        Self::from_data(CompiledData::Mapped(mmap), compiled_path)
+    }
+    /// Returns the path where the compiled source of a file is stored by default,
+    /// which is next to the file with an additional `.compiled` extension.
+    pub fn default_path(source_path: &Path) -> PathBuf {
+        let mut path = source_path.as_os_str().to_owned();
+        path.push(".compiled");
+        PathBuf::from(path)
+    }
+
+    /// Reads the header, headings, introductions and metadata of a compiled source.
+    /// The path of the compiled file is only used in errors.
+    fn from_data(data: CompiledData, compiled_path: &Path) -> Result<Self, SourceError> {
+        let corrupted = || {
+            SourceError::UnexpectedStructure(format!(
+                "Corrupted compiled source: {}",
+                compiled_path.display()
+            ))
+        };
+        let mut header = Decoder::new(&data);
+        if header.bytes(MAGIC.len()) != Some(MAGIC) || header.u32() != Some(FORMAT_VERSION) {
+            return Err(SourceError::Unsupported(format!(
+                "Not a compiled source of a supported version: {}",
+                compiled_path.display()
+            )));
+        }
+        let mut header = Decoder::at(&data, HEADER_LEN - 8).ok_or_else(corrupted)?;
+        let verse_count = header.u32().ok_or_else(corrupted)? as usize;
+        let extras_offset = header.u32().ok_or_else(corrupted)? as usize;
+        if HEADER_LEN + verse_count * VERSE_ENTRY_LEN > data.len() {
+            return Err(corrupted());
+        }
+
+        let mut extras = Decoder::at(&data, extras_offset).ok_or_else(corrupted)?;
+        let headings = (0..extras.u32().ok_or_else(corrupted)?)
+            .map(|_| extras.heading())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(corrupted)?;
+        let introductions = (0..extras.u32().ok_or_else(corrupted)?)
+            .map(|_| Some((Book::from_number(extras.u8()?)?, extras.string()?)))
+            .collect::<Option<HashMap<_, _>>>()
+            .ok_or_else(corrupted)?;
+        let metadata = extras.metadata().ok_or_else(corrupted)?;
+
+        Ok(Self {
+            data,
+            verse_count,
+            headings,
+            introductions,
+            metadata,
+        })
+    }
+
+    /// Returns the content of a verse.
+    pub fn verse(&self, id: VerseId) -> Result<Option<VerseContent>, SourceError> {
+        let position = self.verse_position(|entry_id| entry_id < id)?;
+        if position == self.verse_count || self.verse_entry(position)?.0 != id {
+            return Ok(None);
+        }
+        self.verse_content(position).map(Some)
+    }
+    /// Finds headings that stand before or within the referenced verses, in canonical order.
+    pub fn find_headings(&self, parse_result: &ReferenceParseResult) -> &[Heading] {
+        index::find_headings(&self.headings, parse_result)
+    }
+    /// Finds the title of a Psalm that is part of the original text, e.g. "A Psalm of David."
+    pub fn find_psalm_title(&self, chapter: u8) -> Option<&str> {
+        index::find_psalm_title(&self.headings, chapter)
+    }
+    /// Finds the introduction of a book.
+    pub fn find_introduction(&self, book: Book) -> Option<&str> {
+        self.introductions.get(&book).map(String::as_str)
+    }
+
+    fn find_verses_in_bounds(
+        &self,
+        bounds: VerseIdRange,
+    ) -> Result<Vec<(VerseId, VerseContent)>, SourceError> {
+        let start = self.verse_position(|id| id < bounds.start())?;
+        let end = self.verse_position(|id| id <= bounds.end())?;
+        (start..end)
+            .map(|position| Ok((self.verse_entry(position)?.0, self.verse_content(position)?)))
+            .collect()
+    }
+    /// Returns the position of the first entry of the verse table for which `predicate` is false,
+    /// given that it is true for all entries before it.
+    fn verse_position(&self, predicate: impl Fn(VerseId) -> bool) -> Result<usize, SourceError> {
+        let (mut low, mut high) = (0, self.verse_count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if predicate(self.verse_entry(middle)?.0) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low)
+    }
+    /// Returns the verse ID, content offset and content length of an entry of the verse table.
+    fn verse_entry(&self, position: usize) -> Result<(VerseId, usize, usize), SourceError> {
+        let offset = HEADER_LEN + position * VERSE_ENTRY_LEN;
+        let field = |index: usize| {
+            let start = offset + index * 4;
+            u32::from_le_bytes(self.data[start..start + 4].try_into().unwrap())
+        };
+        // Verse IDs are validated when written, so they're invalid only if the file is corrupted.
+        let id = VerseId::try_from(field(0)).map_err(|_| {
+            SourceError::UnexpectedStructure(format!(
+                "Corrupted verse ID at entry {position} in compiled source"
+            ))
+        })?;
+        Ok((id, field(1) as usize, field(2) as usize))
+    }
+    fn verse_content(&self, position: usize) -> Result<VerseContent, SourceError> {
+        let (id, offset, length) = self.verse_entry(position)?;
+        self.data
+            .get(offset..offset + length)
+            .and_then(|bytes| Decoder::new(bytes).content())
+            .ok_or_else(|| {
+                SourceError::UnexpectedStructure(format!(
+                    "Corrupted content of verse {id} in compiled source"
+                ))
+            })
+    }
+    fn source_stamp(&self) -> SourceStamp {
+        let mut decoder = Decoder::at(&self.data, MAGIC.len() + 4).unwrap();
+        SourceStamp {
+            size: decoder.u64().unwrap_or_default(),
+            modified_secs: decoder.u64().unwrap_or_default(),
+            modified_nanos: decoder.u32().unwrap_or_default(),
+        }
+    }
+
+    /// Encodes a source into the binary format of compiled sources.
+    fn encode(source: &IndexedSource, stamp: SourceStamp) -> Vec<u8> {
+        let verses = source.index().verses();
+        let mut encoder = Encoder::default();
+        encoder.bytes(MAGIC);
+        encoder.u32(FORMAT_VERSION);
+        encoder.u64(stamp.size);
+        encoder.u64(stamp.modified_secs);
+        encoder.u32(stamp.modified_nanos);
+        encoder.u32(verses.len() as u32);
+        let extras_offset_position = encoder.position();
+        encoder.u32(0);
+
+        // Reserve the verse table, which is filled in once the content positions are known.
+        let verse_table_position = encoder.position();
+        encoder.bytes(&vec![0; verses.len() * VERSE_ENTRY_LEN]);
+
+        encoder.set_u32(extras_offset_position, encoder.position() as u32);
+        let headings = source.headings();
+        encoder.u32(headings.len() as u32);
+        for heading in headings {
+            encoder.heading(heading);
+        }
+        let mut introductions = source.introductions().iter().collect::<Vec<_>>();
+        introductions.sort_by_key(|(book, _)| **book);
+        encoder.u32(introductions.len() as u32);
+        for (book, text) in introductions {
+            encoder.u8(book.number());
+            encoder.string(text);
+        }
+        encoder.metadata(source.metadata());
+
+        for (index, (id, content)) in verses.iter().enumerate() {
+            let offset = encoder.position();
+            encoder.content(content);
+            let entry_position = verse_table_position + index * VERSE_ENTRY_LEN;
+            encoder.set_u32(entry_position, u32::from(*id));
+            encoder.set_u32(entry_position + 4, offset as u32);
+            encoder.set_u32(entry_position + 8, (encoder.position() - offset) as u32);
+        }
+
+        encoder.into_bytes()
+    }
+    /// Writes a compiled source into a file. The file is written next to its final path first
+    /// and then renamed, so that sources already open are not affected. The temporary file
+    /// is unique to the process and the write, so that processes compiling the same source
+    /// at the same time don't write into each other's files.
+    fn write(data: &[u8], compiled_path: &Path) -> Result<(), SourceError> {
+        let mut temporary_path = compiled_path.as_os_str().to_owned();
+        temporary_path.push(format!(
+            ".{}-{}.tmp",
+            process::id(),
+            WRITE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temporary_path = PathBuf::from(temporary_path);
+        fs::write(&temporary_path, data)
+            .map_err(|error| SourceError::io(&temporary_path, error))?;
+        fs::rename(&temporary_path, compiled_path).map_err(|error| {
+            let _ = fs::remove_file(&temporary_path);
+            SourceError::io(compiled_path, error)
+        })
+    }
+}
+
+/// Bytes of a compiled source, either mapped from its file or in memory.
+enum CompiledData {
+    Mapped(Mmap),
+    InMemory(Vec<u8>),
+}
+impl Deref for CompiledData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            CompiledData::Mapped(mmap) => mmap,
+            CompiledData::InMemory(data) => data,
+        }
+    }
+}
+
+/// Total size and latest modification time of the files of a source,
+/// which change when any of the files changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct SourceStamp {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+impl SourceStamp {
+    fn of_files(paths: &[impl AsRef<Path>]) -> Result<Self, SourceError> {
+        let mut size = 0;
+        let mut latest_modified = Duration::ZERO;
+        for path in paths {
+            let path = path.as_ref();
+            let metadata = fs::metadata(path).map_err(|error| SourceError::io(path, error))?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            size += metadata.len();
+            latest_modified = latest_modified.max(modified);
+        }
+        Ok(Self {
+            size,
+            modified_secs: latest_modified.as_secs(),
+            modified_nanos: latest_modified.subsec_nanos(),
+        })
+    }
+}
+
+/// Writes values in the little-endian binary encoding of compiled sources.
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+impl Encoder {
+    fn position(&self) -> usize {
+        self.bytes.len()
+    }
+    fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+    fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+    /// Overwrites a value written earlier.
+    fn set_u32(&mut self, position: usize, value: u32) {
+        self.bytes[position..position + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes(value.as_bytes());
+    }
+    fn optional_string(&mut self, value: Option<&str>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.string(value);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn content(&mut self, content: &VerseContent) {
+        self.u32(content.segments().len() as u32);
+        for segment in content.segments() {
+            match segment {
+                Segment::Text { text, style } => {
+                    self.u8(0);
+                    self.string(text);
+                    self.style(*style);
+                }
+                Segment::Word(word) => {
+                    self.u8(1);
+                    self.string(&word.text);
+                    self.style(word.style);
+                    self.u16(word.strongs.len() as u16);
+                    for strongs in word.strongs.iter() {
+                        match strongs {
+                            StrongsNumber::Hebrew(number) => {
+                                self.u8(b'H');
+                                self.u16(*number);
+                            }
+                            StrongsNumber::Greek(number) => {
+                                self.u8(b'G');
+                                self.u16(*number);
+                            }
+                        }
+                    }
+                    self.u16(word.morphology.len() as u16);
+                    for morphology in word.morphology.iter() {
+                        self.optional_string(morphology.scheme.as_deref());
+                        self.string(&morphology.code);
+                    }
+                }
+                Segment::Note(note) => {
+                    self.u8(2);
+                    match &note.note_type {
+                        None => self.u8(0),
+                        Some(NoteType::CrossReference) => self.u8(1),
+                        Some(NoteType::Translation) => self.u8(2),
+                        Some(NoteType::Study) => self.u8(3),
+                        Some(NoteType::Other(note_type)) => {
+                            self.u8(4);
+                            self.string(note_type);
+                        }
+                    }
+                    self.string(&note.text);
+                    self.u16(note.references.len() as u16);
+                    for reference in note.references.iter() {
+                        self.u32(reference.start().into());
+                        self.u32(reference.end().into());
+                    }
+                }
+                Segment::Break(layout_break) => {
+                    self.u8(3);
+                    match layout_break {
+                        Break::Paragraph => self.u8(0),
+                        Break::Stanza => self.u8(1),
+                        Break::Line { indent } => {
+                            self.u8(2);
+                            self.u8(*indent);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    fn style(&mut self, style: TextStyle) {
+        let flags = [
+            style.added,
+            style.divine_name,
+            style.italic,
+            style.quotation,
+            style.words_of_christ,
+        ];
+        let bits = flags
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (index, flag)| bits | (*flag as u8) << index);
+        self.u8(bits);
+    }
+    fn heading(&mut self, heading: &Heading) {
+        match &heading.heading_type {
+            HeadingType::Book => self.u8(0),
+            HeadingType::Chapter => self.u8(1),
+            HeadingType::Section => self.u8(2),
+            HeadingType::Psalm => self.u8(3),
+            HeadingType::Acrostic => self.u8(4),
+            HeadingType::Other(heading_type) => {
+                self.u8(5);
+                self.string(heading_type);
+            }
+        }
+        self.u8(heading.canonical as u8);
+        self.string(&heading.text);
+        self.u32(heading.verse_id.into());
+    }
+    fn metadata(&mut self, metadata: &SourceMetadata) {
+        for field in [
+            &metadata.title,
+            &metadata.abbreviation,
+            &metadata.language,
+            &metadata.publisher,
+            &metadata.rights,
+            &metadata.versification,
+            &metadata.identifier,
+        ] {
+            self.optional_string(field.as_deref());
+        }
+    }
+}
+
+/// Reads values of the binary encoding written by [`Encoder`].
+/// Each read returns `None` if the bytes end or are not valid.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+    fn at(bytes: &'a [u8], position: usize) -> Option<Self> {
+        (position <= bytes.len()).then_some(Self { bytes, position })
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).ok()
+    }
+    fn optional_string(&mut self) -> Option<Option<String>> {
+        match self.u8()? {
+            0 => Some(None),
+            _ => self.string().map(Some),
+        }
+    }
+    fn verse_id(&mut self) -> Option<VerseId> {
+        VerseId::try_from(self.u32()?).ok()
+    }
+
+    fn content(&mut self) -> Option<VerseContent> {
+        let segments = (0..self.u32()?)
+            .map(|_| self.segment())
+            .collect::<Option<Vec<_>>>()?;
+        Some(VerseContent::new(segments))
+    }
+    fn segment(&mut self) -> Option<Segment> {
+        match self.u8()? {
+            0 => Some(Segment::Text {
+                text: self.string()?,
+                style: self.style()?,
+            }),
+            1 => {
+                let text = self.string()?;
+                let style = self.style()?;
+                let strongs = (0..self.u16()?)
+                    .map(|_| match self.u8()? {
+                        b'H' => Some(StrongsNumber::Hebrew(self.u16()?)),
+                        b'G' => Some(StrongsNumber::Greek(self.u16()?)),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let morphology = (0..self.u16()?)
+                    .map(|_| {
+                        Some(MorphologyCode {
+                            scheme: self.optional_string()?,
+                            code: self.string()?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Segment::Word(Word {
+                    text,
+                    style,
+                    strongs,
+                    morphology,
+                }))
+            }
+            2 => {
+                let note_type = match self.u8()? {
+                    0 => None,
+                    1 => Some(NoteType::CrossReference),
+                    2 => Some(NoteType::Translation),
+                    3 => Some(NoteType::Study),
+                    4 => Some(NoteType::Other(self.string()?)),
+                    _ => return None,
+                };
+                let text = self.string()?;
+                let references = (0..self.u16()?)
+                    .map(|_| VerseIdRange::new(self.verse_id()?, self.verse_id()?))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Segment::Note(Note {
+                    note_type,
+                    text,
+                    references,
+                }))
+            }
+            3 => Some(Segment::Break(match self.u8()? {
+                0 => Break::Paragraph,
+                1 => Break::Stanza,
+                2 => Break::Line { indent: self.u8()? },
+                _ => return None,
+            })),
+            _ => None,
+        }
+    }
+    fn style(&mut self) -> Option<TextStyle> {
+        let bits = self.u8()?;
+        let flag = |index: u8| bits & (1 << index) != 0;
+        Some(TextStyle {
+            added: flag(0),
+            divine_name: flag(1),
+            italic: flag(2),
+            quotation: flag(3),
+            words_of_christ: flag(4),
+        })
+    }
+    fn heading(&mut self) -> Option<Heading> {
+        let heading_type = match self.u8()? {
+            0 => HeadingType::Book,
+            1 => HeadingType::Chapter,
+            2 => HeadingType::Section,
+            3 => HeadingType::Psalm,
+            4 => HeadingType::Acrostic,
+            5 => HeadingType::Other(self.string()?),
+            _ => return None,
+        };
+        Some(Heading {
+            heading_type,
+            canonical: self.u8()? != 0,
+            text: self.string()?,
+            verse_id: self.verse_id()?,
+        })
+    }
+    fn metadata(&mut self) -> Option<SourceMetadata> {
+        Some(SourceMetadata {
+            title: self.optional_string()?,
+            abbreviation: self.optional_string()?,
+            language: self.optional_string()?,
+            publisher: self.optional_string()?,
+            rights: self.optional_string()?,
+            versification: self.optional_string()?,
+            identifier: self.optional_string()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_reference;
+
+    const OSIS: &str = r#"<osis><osisText osisIDWork="KJV">
+        <header><work osisWork="KJV"><title>King James Version</title></work></header>
+        <div type="book" osisID="John">
+          <div type="introduction"><p>The Gospel according to John.</p></div>
+          <chapter osisID="John.3">
+            <title>The new birth</title>
+            <p><verse osisID="John.3.16">For God so loved the world<note type="crossReference"><reference osisRef="Rom.5.8">Rom. 5:8</reference></note>, that he gave his <w lemma="strong:G3439" morph="robinson:A-ASM">only begotten</w> Son.</verse>
+            <verse osisID="John.3.17">For God sent not his Son into the world to condemn <transChange type="added">the</transChange> world;</verse></p>
+          </chapter>
+        </div>
+        </osisText></osis>"#;
+
+    #[test]
+    fn compile_source_and_rebuild_when_source_changes() {
+        let directory = std::env::temp_dir().join(format!(
+            "raqiya-bible-reference-compiled-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        let osis_path = directory.join("john.xml");
+        let compiled_path = CompiledSource::default_path(&osis_path);
+        fs::write(&osis_path, OSIS).unwrap();
+
+        let compiled_source = CompiledSource::open_osis(&osis_path, &compiled_path).unwrap();
+        let osis_source = OsisSource::from_data(OSIS.into());
+        for reference in ["John 3", "John 3:17", "John 3:16-17"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                compiled_source.find_content(&parse_result).unwrap(),
+                osis_source.find_content(&parse_result).unwrap()
+            );
+            assert_eq!(
+                compiled_source.find_headings(&parse_result),
+                osis_source.find_headings(&parse_result).unwrap()
+            );
+        }
+        assert_eq!(
+            compiled_source.find_introduction(Book::John),
+            Some("The Gospel according to John.")
+        );
+        assert_eq!(compiled_source.metadata(), osis_source.metadata());
+        assert_eq!(
+            compiled_source.metadata().title.as_deref(),
+            Some("King James Version")
+        );
+        assert_eq!(
+            compiled_source
+                .verse(VerseId::new(Book::John, 3, 18))
+                .unwrap(),
+            None
+        );
+
+        // Changing the source changes its size, so the compiled source is rebuilt.
+        fs::write(
+            &osis_path,
+            OSIS.replace("For God so loved", "For God so greatly loved"),
+        )
+        .unwrap();
+        let compiled_source = CompiledSource::open_osis(&osis_path, &compiled_path).unwrap();
+        assert!(compiled_source
+            .verse(VerseId::new(Book::John, 3, 16))
+            .unwrap()
+            .unwrap()
+            .plain_text()
+            .starts_with("For God so greatly loved"));
+
+        // A compiled file that can't be written is kept in memory instead.
+        let compiled_source =
+            CompiledSource::open_osis(&osis_path, &directory.join("missing/john.xml.compiled"))
+                .unwrap();
+        assert!(compiled_source
+            .verse(VerseId::new(Book::John, 3, 16))
+            .unwrap()
+            .is_some());
+        assert!(!directory.join("missing").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,437 @@
+use std::fmt;
+
+use crate::{VerseId, VerseIdRange};
+
+/// Content of a verse as a sequence of inline segments, which keeps the markup of the source,
+/// such as words added by translators, divine names, quotations and notes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VerseContent {
+    segments: Vec<Segment>,
+}
+impl VerseContent {
+    pub fn new(segments: Vec<Segment>) -> Self {
+        Self { segments }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+    /// Returns the text of the verse without markup and notes.
+    pub fn plain_text(&self) -> String {
+        self.segments.iter().filter_map(Segment::text).collect()
+    }
+    /// Returns the words tagged with Strong's numbers or morphology.
+    pub fn words(&self) -> impl Iterator<Item = &Word> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Word(word) => Some(word),
+            _ => None,
+        })
+    }
+    /// Returns the notes, each with its anchor, which is the byte offset in the plain text
+    /// where the note is attached.
+    pub fn notes(&self) -> Vec<(usize, &Note)> {
+        let mut offset = 0;
+        let mut notes = Vec::new();
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Note(note) => notes.push((offset, note)),
+                _ => offset += segment.text().map_or(0, str::len),
+            }
+        }
+        notes
+    }
+    /// Returns whether any word is tagged with the given Strong's number.
+    pub fn contains_strongs(&self, strongs: StrongsNumber) -> bool {
+        self.words().any(|word| word.strongs.contains(&strongs))
+    }
+    /// Returns each continuous span of words of Christ as plain text.
+    ///
+    /// A span continues over changes of other styles and over notes,
+    /// e.g. a word added by translators within the words of Christ.
+    pub fn words_of_christ(&self) -> Vec<String> {
+        let mut spans = Vec::new();
+        let mut current_span: Option<String> = None;
+        for segment in self.segments.iter() {
+            match (segment.text(), segment.style()) {
+                (Some(text), Some(style)) if style.words_of_christ => {
+                    current_span.get_or_insert_with(String::new).push_str(text);
+                }
+                (Some(_), _) => spans.extend(current_span.take()),
+                _ => {}
+            }
+        }
+        spans.extend(current_span);
+        spans
+            .into_iter()
+            .map(|span| span.trim().to_string())
+            .filter(|span| !span.is_empty())
+            .collect()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+    /// Returns the content with only the text segments of words of Christ.
+    /// Words of Christ that other text stood between are separated by a space.
+    pub(crate) fn retain_words_of_christ(self) -> Self {
+        let mut segments = Vec::new();
+        let mut is_separated = false;
+        for segment in self.segments {
+            match segment.style() {
+                Some(style) if style.words_of_christ => {
+                    if is_separated && !segments.is_empty() {
+                        segments.push(Segment::Text {
+                            text: " ".to_string(),
+                            style,
+                        });
+                    }
+                    segments.push(segment);
+                    is_separated = false;
+                }
+                Some(_) => is_separated = true,
+                None => {}
+            }
+        }
+        let mut content = Self::new(segments);
+        content.collapse_whitespace();
+        content
+    }
+
+    /// Appends text in the given style, merging it into the last segment if the style is the same.
+    pub(crate) fn push_text(&mut self, text: &str, style: TextStyle) {
+        if let Some(Segment::Text {
+            text: last_text,
+            style: last_style,
+        }) = self.segments.last_mut()
+        {
+            if *last_style == style {
+                last_text.push_str(text);
+                return;
+            }
+        }
+        self.segments.push(Segment::Text {
+            text: text.to_string(),
+            style,
+        });
+    }
+    pub(crate) fn push(&mut self, segment: Segment) {
+        self.segments.push(segment);
+    }
+    /// Collapses runs of whitespace into single spaces across segments
+    /// and trims whitespace from the start and the end of the content.
+    ///
+    /// Whitespace in XML sources comes mostly from formatting and element boundaries,
+    /// so it is not meaningful as such.
+    pub(crate) fn collapse_whitespace(&mut self) {
+        // Treat the start as whitespace to trim leading whitespace.
+        let mut previous_is_whitespace = true;
+        for segment in self.segments.iter_mut() {
+            if let Some(text) = segment.text_mut() {
+                let mut collapsed = String::with_capacity(text.len());
+                for c in text.chars() {
+                    if c.is_whitespace() {
+                        if !previous_is_whitespace {
+                            collapsed.push(' ');
+                        }
+                        previous_is_whitespace = true;
+                    } else {
+                        collapsed.push(c);
+                        previous_is_whitespace = false;
+                    }
+                }
+                *text = collapsed;
+            }
+        }
+        // Trim trailing whitespace, which is in the last non-empty text segment.
+        if let Some(text) = self
+            .segments
+            .iter_mut()
+            .rev()
+            .filter_map(Segment::text_mut)
+            .find(|text| !text.is_empty())
+        {
+            text.truncate(text.trim_end().len());
+        }
+        self.segments
+            .retain(|segment| segment.text().is_none_or(|text| !text.is_empty()));
+    }
+}
+impl From<&str> for VerseContent {
+    /// Creates content of plain text only.
+    fn from(text: &str) -> Self {
+        let mut content = VerseContent::default();
+        content.push_text(text, TextStyle::default());
+        content
+    }
+}
+impl fmt::Display for VerseContent {
+    /// Formats the content as plain text.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.plain_text())
+    }
+}
+
+/// Inline segment of verse content.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// Text in a certain style.
+    Text { text: String, style: TextStyle },
+    /// Word tagged with Strong's numbers or morphology.
+    Word(Word),
+    /// Note attached to the position between the surrounding text segments.
+    Note(Note),
+    /// Break in the layout, e.g. the start of a paragraph or a poetic line.
+    Break(Break),
+}
+impl Segment {
+    /// Returns the text of a text or word segment.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Segment::Text { text, .. } => Some(text),
+            Segment::Word(word) => Some(&word.text),
+            Segment::Note(_) | Segment::Break(_) => None,
+        }
+    }
+    /// Returns the style of a text or word segment.
+    pub fn style(&self) -> Option<TextStyle> {
+        match self {
+            Segment::Text { style, .. } => Some(*style),
+            Segment::Word(word) => Some(word.style),
+            Segment::Note(_) | Segment::Break(_) => None,
+        }
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Segment::Text { text, .. } => Some(text),
+            Segment::Word(word) => Some(&mut word.text),
+            Segment::Note(_) | Segment::Break(_) => None,
+        }
+    }
+}
+
+/// Break in the layout of a passage. A break at the start of verse content
+/// means that the verse starts a new paragraph, stanza or line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Break {
+    /// Start of a prose paragraph.
+    Paragraph,
+    /// Start of a stanza, i.e. a group of poetic lines.
+    Stanza,
+    /// Start of a poetic line, indented by the given number of levels.
+    Line { indent: u8 },
+}
+
+/// Style of a text segment. Styles combine when markup is nested,
+/// e.g. a word added by translators within a quotation.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TextStyle {
+    /// Words supplied by translators that are not in the original text,
+    /// e.g. the italicised words of the KJV.
+    pub added: bool,
+    /// Divine name, e.g. "LORD" printed in small capitals.
+    pub divine_name: bool,
+    /// Text in italics for other reasons than being added by translators.
+    pub italic: bool,
+    /// Quoted speech.
+    pub quotation: bool,
+    /// Words spoken by Jesus, printed in red in red-letter editions.
+    pub words_of_christ: bool,
+}
+
+/// Word of the translation tagged with the original language words it translates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    /// Text of the word as it appears in the translation.
+    pub text: String,
+    pub style: TextStyle,
+    /// Strong's numbers of the original language words.
+    pub strongs: Vec<StrongsNumber>,
+    /// Morphology codes of the original language words.
+    pub morphology: Vec<MorphologyCode>,
+}
+
+/// Number of a Hebrew or Greek word in Strong's concordance, e.g. H430 or G26.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum StrongsNumber {
+    Hebrew(u16),
+    Greek(u16),
+}
+impl StrongsNumber {
+    /// Parses a Strong's number of the form "G26", allowing leading zeros, e.g. "H07225",
+    /// and ignoring letter suffixes of extended numbering, e.g. "H1254a".
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut chars = value.chars();
+        let language = chars.next()?;
+        let digits = chars
+            .as_str()
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let number = digits.parse::<u16>().ok()?;
+        match language.to_ascii_uppercase() {
+            'H' => Some(Self::Hebrew(number)),
+            'G' => Some(Self::Greek(number)),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for StrongsNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hebrew(number) => write!(f, "H{number}"),
+            Self::Greek(number) => write!(f, "G{number}"),
+        }
+    }
+}
+
+/// Morphology code of an original language word, e.g. "N-ASF" of the Robinson scheme.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MorphologyCode {
+    /// Scheme of the code as given by the source, e.g. "robinson" or "strongMorph".
+    pub scheme: Option<String>,
+    pub code: String,
+}
+
+/// Note by translators or editors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note {
+    pub note_type: Option<NoteType>,
+    pub text: String,
+    /// Verses the note refers to, mainly of cross-reference notes.
+    pub references: Vec<VerseIdRange>,
+}
+
+/// Type of a note.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NoteType {
+    /// Cross-reference to related passages.
+    CrossReference,
+    /// Explanation of the translation, e.g. an alternative rendering of the original.
+    Translation,
+    /// Study note explaining the meaning or background of the text.
+    Study,
+    /// Other type as given by the source.
+    Other(String),
+}
+impl NoteType {
+    /// Returns the note type of the value of an OSIS `type` attribute, e.g. "crossReference".
+    pub fn from_osis_type(value: &str) -> Self {
+        match value {
+            "crossReference" => Self::CrossReference,
+            "translation" => Self::Translation,
+            "study" => Self::Study,
+            _ => Self::Other(value.to_string()),
+        }
+    }
+}
+
+/// Heading that stands before a verse, e.g. a section heading or the title of a Psalm.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heading {
+    pub heading_type: HeadingType,
+    /// Whether the heading is part of the original text, like the titles of many Psalms,
+    /// rather than added by editors.
+    pub canonical: bool,
+    pub text: String,
+    /// Verse the heading stands before, or within in case of some Psalm titles.
+    pub verse_id: VerseId,
+}
+
+impl Heading {
+    /// Returns whether the heading is the title of a Psalm, e.g. "A Psalm of David."
+    ///
+    /// Other canonical headings, e.g. the Hebrew letters of Psalm 119, are not Psalm titles.
+    pub fn is_psalm_title(&self) -> bool {
+        self.heading_type == HeadingType::Psalm
+    }
+}
+
+/// Type of a heading.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeadingType {
+    /// Title of a book.
+    Book,
+    /// Title of a chapter, e.g. "PSALM 23".
+    Chapter,
+    /// Heading of a section or pericope.
+    Section,
+    /// Title of a Psalm, e.g. "A Psalm of David."
+    Psalm,
+    /// Letter of an acrostic section, e.g. "ALEPH" in Psalm 119.
+    Acrostic,
+    /// Other type as given by the source.
+    Other(String),
+}
+impl HeadingType {
+    /// Returns the heading type of the value of an OSIS `type` attribute of a `<title>`, if any.
+    pub fn from_osis_type(value: Option<&str>) -> Self {
+        match value {
+            Some("main") => Self::Book,
+            Some("chapter") => Self::Chapter,
+            None | Some("section") => Self::Section,
+            Some("psalm") => Self::Psalm,
+            Some("acrostic") => Self::Acrostic,
+            Some(value) => Self::Other(value.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_strongs_numbers() {
+        assert_eq!(StrongsNumber::parse("G26"), Some(StrongsNumber::Greek(26)));
+        assert_eq!(
+            StrongsNumber::parse("H07225"),
+            Some(StrongsNumber::Hebrew(7225))
+        );
+        assert_eq!(
+            StrongsNumber::parse("H1254a"),
+            Some(StrongsNumber::Hebrew(1254))
+        );
+        assert_eq!(StrongsNumber::parse("X26"), None);
+        assert_eq!(StrongsNumber::parse("G"), None);
+        assert_eq!(StrongsNumber::Hebrew(430).to_string(), "H430");
+    }
+    #[test]
+    fn collapse_whitespace_across_segments() {
+        let added = TextStyle {
+            added: true,
+            ..TextStyle::default()
+        };
+        let mut content = VerseContent::default();
+        content.push_text("\n  In the ", TextStyle::default());
+        content.push_text(" beginning ", added);
+        content.push(Segment::Note(Note {
+            note_type: None,
+            text: String::from("Or, first"),
+            references: vec![],
+        }));
+        content.push_text("\n ", TextStyle::default());
+        content.collapse_whitespace();
+
+        assert_eq!(
+            content.segments(),
+            [
+                Segment::Text {
+                    text: String::from("In the "),
+                    style: TextStyle::default()
+                },
+                Segment::Text {
+                    text: String::from("beginning"),
+                    style: added
+                },
+                Segment::Note(Note {
+                    note_type: None,
+                    text: String::from("Or, first"),
+                    references: vec![],
+                }),
+            ]
+        );
+        assert_eq!(content.plain_text(), "In the beginning");
+        assert_eq!(content.notes()[0].0, "In the beginning".len());
+    }
+}
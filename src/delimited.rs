@@ -0,0 +1,384 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use crate::{
+    Book, IndexedSource, Locale, Reference, ReferenceParseResult, Source, SourceError,
+    VerseContent, VerseId, VerseIndex,
+};
+
+/// Layout of the lines of a delimited-text source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineLayout {
+    /// Book, chapter, verse and text in columns separated by a delimiter,
+    /// e.g. "Gen\t1\t1\tIn the beginning".
+    ///
+    /// The last column contains the rest of the line, so text in the last column
+    /// may contain the delimiter.
+    Columns {
+        delimiter: char,
+        columns: ColumnMapping,
+    },
+    /// Reference followed by text, e.g. "Gen 1:1 In the beginning".
+    ReferencePrefix,
+}
+
+/// Positions of columns, starting from 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColumnMapping {
+    pub book: usize,
+    pub chapter: usize,
+    pub verse: usize,
+    pub text: usize,
+}
+impl Default for ColumnMapping {
+    /// Book, chapter, verse and text in this order.
+    fn default() -> Self {
+        Self {
+            book: 0,
+            chapter: 1,
+            verse: 2,
+            text: 3,
+        }
+    }
+}
+
+/// Whether the first line of a delimited-text source is a header.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeaderMode {
+    /// The first line is a header if it can't be read as a verse and either its column names
+    /// are recognised or none of its book, chapter and verse fields look like verse data.
+    #[default]
+    Detect,
+    Present,
+    Absent,
+}
+
+/// Configuration for reading a delimited-text source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DelimitedConfig {
+    pub layout: LineLayout,
+    pub header: HeaderMode,
+}
+impl DelimitedConfig {
+    /// Tab-separated values with the columns book, chapter, verse and text.
+    pub fn tsv() -> Self {
+        Self::columns('\t')
+    }
+    /// Values separated by the given delimiter with the columns book, chapter, verse and text.
+    pub fn columns(delimiter: char) -> Self {
+        Self {
+            layout: LineLayout::Columns {
+                delimiter,
+                columns: ColumnMapping::default(),
+            },
+            header: HeaderMode::Detect,
+        }
+    }
+    /// Lines of a reference followed by text, e.g. "Gen 1:1 In the beginning".
+    pub fn reference_lines() -> Self {
+        Self {
+            layout: LineLayout::ReferencePrefix,
+            header: HeaderMode::Detect,
+        }
+    }
+}
+impl Default for DelimitedConfig {
+    fn default() -> Self {
+        Self::tsv()
+    }
+}
+
+/// Represents a source of Bible content in plain text with a verse per line,
+/// either in delimited columns or as a reference followed by text.
+///
+/// Book names are resolved like in references, so they may be full names, abbreviations
+/// or OSIS book IDs, e.g. "Genesis", "Gen." or "1John". Books may also be given
+/// by their numbers in canonical order, 1 being Genesis and 66 Revelation.
+///
+/// If the source has a header with the column names "book", "chapter", "verse" and "text",
+/// in any letter case, the columns are mapped by their names instead of the configuration.
+/// Empty lines are skipped.
+///
+/// The whole source is parsed when created.
+pub struct DelimitedSource {
+    source: IndexedSource,
+}
+impl Source for DelimitedSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+}
+impl DelimitedSource {
+    pub fn from_file(file: File, config: &DelimitedConfig) -> Result<Self, SourceError> {
+        let lines = BufReader::new(file)
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| SourceError::Io { path: None, error })?;
+        Self::from_lines(lines.iter().map(String::as_str), config)
+    }
+    /// Creates a source from delimited text already in memory.
+    pub fn from_text(text: &str, config: &DelimitedConfig) -> Result<Self, SourceError> {
+        Self::from_lines(text.lines(), config)
+    }
+
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+
+    fn from_lines<'a>(
+        lines: impl Iterator<Item = &'a str>,
+        config: &DelimitedConfig,
+    ) -> Result<Self, SourceError> {
+        let mut layout = config.layout;
+        let mut verses = Vec::new();
+        let mut lines = lines
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+
+        if let Some((_, first_line)) = lines.peek() {
+            let is_header = match config.header {
+                HeaderMode::Detect => is_header(first_line, &layout),
+                HeaderMode::Present => true,
+                HeaderMode::Absent => false,
+            };
+            if is_header {
+                if let LineLayout::Columns { delimiter, columns } = &mut layout {
+                    if let Some(header_columns) = map_header_columns(first_line, *delimiter) {
+                        *columns = header_columns;
+                    }
+                }
+                lines.next();
+            }
+        }
+        for (index, line) in lines {
+            let verse = read_line(line, &layout).map_err(|err| {
+                SourceError::UnexpectedStructure(format!("Line {}: {err}", index + 1))
+            })?;
+            verses.push(verse);
+        }
+
+        Ok(Self {
+            source: IndexedSource::new(VerseIndex::new(verses), vec![], vec![]),
+        })
+    }
+}
+
+/// Returns whether the first line of a source looks like a header rather than a verse,
+/// so that a verse with a typo on the first line is an error instead of being left out.
+fn is_header(line: &str, layout: &LineLayout) -> bool {
+    if read_line(line, layout).is_ok() {
+        return false;
+    }
+    match layout {
+        LineLayout::Columns { delimiter, columns } => {
+            if map_header_columns(line, *delimiter).is_some() {
+                return true;
+            }
+            let fields = line.split(*delimiter).collect::<Vec<_>>();
+            let is_verse_data = |index: usize| {
+                fields.get(index).is_some_and(|field| {
+                    let field = field.trim();
+                    field.parse::<u8>().is_ok() || Book::from_name(field, Locale::En).is_some()
+                })
+            };
+            ![columns.book, columns.chapter, columns.verse]
+                .into_iter()
+                .any(is_verse_data)
+        }
+        LineLayout::ReferencePrefix => split_reference_prefix(line).is_err(),
+    }
+}
+/// Reads the verse of a line.
+fn read_line(line: &str, layout: &LineLayout) -> Result<(VerseId, VerseContent), String> {
+    let (book_name, chapter, verse, text) = match layout {
+        LineLayout::Columns { delimiter, columns } => {
+            let column_count = [columns.book, columns.chapter, columns.verse, columns.text]
+                .into_iter()
+                .max()
+                .unwrap_or_default()
+                + 1;
+            let fields = line.splitn(column_count, *delimiter).collect::<Vec<_>>();
+            let field = |index: usize| {
+                fields
+                    .get(index)
+                    .copied()
+                    .ok_or_else(|| format!("Expected {column_count} columns"))
+            };
+            (
+                field(columns.book)?,
+                field(columns.chapter)?,
+                field(columns.verse)?,
+                field(columns.text)?,
+            )
+        }
+        LineLayout::ReferencePrefix => split_reference_prefix(line)?,
+    };
+
+    let book_name = book_name.trim();
+    let book = match book_name.parse::<u8>() {
+        Ok(number) => Book::from_number(number),
+        Err(_) => Book::from_name(book_name, Locale::En),
+    }
+    .ok_or_else(|| format!("Unknown book name: {book_name}"))?;
+    let chapter = parse_number(chapter, "chapter")?;
+    let verse = parse_number(verse, "verse")?;
+    Ok((
+        VerseId::new(book, chapter, verse),
+        VerseContent::from(text.trim()),
+    ))
+}
+/// Splits a line starting with a reference, e.g. "1 John 4:8 He that loveth not",
+/// into its book name, chapter, verse and text.
+fn split_reference_prefix(line: &str) -> Result<(&str, &str, &str, &str), String> {
+    // The book name may contain spaces, so the reference ends with the first word
+    // that is a chapter and a verse.
+    let mut offset = 0;
+    for word in line.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += word.len();
+        let Some((chapter, verse)) = word.trim_end().split_once(':') else {
+            continue;
+        };
+        let is_number =
+            |value: &str| !value.is_empty() && value.chars().all(|c| c.is_ascii_digit());
+        if start > 0 && is_number(chapter) && is_number(verse) {
+            return Ok((&line[..start], chapter, verse, &line[offset..]));
+        }
+    }
+    Err("Expected a reference like \"Gen 1:1\" at the start of the line".to_string())
+}
+/// Maps the columns by the column names of a header, if it has all of them.
+fn map_header_columns(header: &str, delimiter: char) -> Option<ColumnMapping> {
+    let names = header
+        .split(delimiter)
+        .map(|name| name.trim().to_lowercase())
+        .collect::<Vec<_>>();
+    let position = |name: &str| names.iter().position(|column_name| column_name == name);
+    Some(ColumnMapping {
+        book: position("book")?,
+        chapter: position("chapter")?,
+        verse: position("verse")?,
+        text: position("text")?,
+    })
+}
+/// Parses a chapter or verse number, which must be positive.
+pub(crate) fn parse_number(value: &str, name: &str) -> Result<u8, String> {
+    let value = value.trim();
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|number| *number > 0)
+        .ok_or_else(|| format!("Invalid {name} number: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_reference;
+
+    fn plain_texts(source: &DelimitedSource, reference: &str) -> Vec<String> {
+        source
+            .find_content(&parse_reference(reference).unwrap())
+            .unwrap()
+            .iter()
+            .map(|reference| reference.content.plain_text())
+            .collect()
+    }
+
+    #[test]
+    fn read_columns_with_and_without_header() {
+        let source = DelimitedSource::from_text(
+            "Gen\t1\t1\tIn the beginning God created the heaven and the earth.\n\
+             \n\
+             Genesis\t1\t2\tAnd the earth was without form, and void;\tand darkness\n\
+             1\t1\t3\tAnd God said, Let there be light: and there was light.\n",
+            &DelimitedConfig::tsv(),
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "Genesis 1:2-3"),
+            [
+                "And the earth was without form, and void;\tand darkness",
+                "And God said, Let there be light: and there was light."
+            ]
+        );
+
+        let source = DelimitedSource::from_text(
+            "Text,Verse,Chapter,Book\n\
+             In the beginning was the Word,1,1,John\n",
+            &DelimitedConfig::columns(','),
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "John 1:1"),
+            ["In the beginning was the Word"]
+        );
+
+        let error = DelimitedSource::from_text(
+            "book\tchapter\tverse\ttext\nGen\t1\t1\tIn the beginning\nJhon\t1\t1\tIn the beginning\n",
+            &DelimitedConfig::tsv(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected structure: Line 3: Unknown book name: Jhon"
+        );
+
+        // A verse with a typo on the first line is not taken for a header.
+        let error = DelimitedSource::from_text(
+            "Gne\t1\t1\tIn the beginning\nGen\t1\t2\tAnd the earth\n",
+            &DelimitedConfig::tsv(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected structure: Line 1: Unknown book name: Gne"
+        );
+        let source = DelimitedSource::from_text(
+            "Kirja\tLuku\tJae\tTeksti\nJohn\t11\t35\tJeesus itki.\n",
+            &DelimitedConfig::tsv(),
+        )
+        .unwrap();
+        assert_eq!(plain_texts(&source, "John 11:35"), ["Jeesus itki."]);
+    }
+    #[test]
+    fn read_reference_lines() {
+        let source = DelimitedSource::from_text(
+            "1 John 4:8 He that loveth not knoweth not God; for God is love.\n\
+             Song of Solomon 2:1 I am the rose of Sharon, and the lily of the valleys.\n",
+            &DelimitedConfig::reference_lines(),
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "1 John 4:8"),
+            ["He that loveth not knoweth not God; for God is love."]
+        );
+        assert_eq!(
+            plain_texts(&source, "Song of Solomon 2"),
+            ["I am the rose of Sharon, and the lily of the valleys."]
+        );
+
+        let config = DelimitedConfig {
+            header: HeaderMode::Absent,
+            ..DelimitedConfig::reference_lines()
+        };
+        assert!(
+            DelimitedSource::from_text("Reference Text\nGen 1:1 In the beginning", &config)
+                .is_err()
+        );
+        assert!(DelimitedSource::from_text(
+            "Gne 1:1 In the beginning\nGen 1:2 And the earth",
+            &DelimitedConfig::reference_lines()
+        )
+        .is_err());
+    }
+}
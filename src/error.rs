@@ -0,0 +1,123 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use xml::common::Position;
+
+use crate::Missing;
+
+/// Error of reading a source or finding content in it.
+#[derive(Debug)]
+pub enum SourceError {
+    /// Reading or writing a file failed. The path is known unless the source was read
+    /// from an already open file.
+    Io {
+        path: Option<PathBuf>,
+        error: io::Error,
+    },
+    /// XML that isn't well-formed, at a line and a column starting from 1.
+    MalformedXml {
+        line: u64,
+        column: u64,
+        message: String,
+    },
+    /// JSON that isn't well-formed, at a line and a column starting from 1.
+    MalformedJson {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Content that is well-formed but not structured as its format requires,
+    /// e.g. a verse with an unknown book or a corrupted compiled source.
+    UnexpectedStructure(String),
+    /// Content in a format or a variant of a format that isn't supported,
+    /// e.g. a SWORD module in a versification other than KJV.
+    Unsupported(String),
+    /// Name of a source that isn't in a library, e.g. "ASV".
+    UnknownSource(String),
+    /// Referenced books, chapters or verses that the source doesn't have.
+    ReferenceNotFound { missing: Vec<Missing> },
+    /// Range of verses that ends past the last verse of its chapter in the source,
+    /// e.g. "Jude 1:24-26" when Jude has 25 verses.
+    RangeExceedsChapter {
+        chapter: u8,
+        number_from: u8,
+        number_to: u8,
+        last_number: u8,
+    },
+}
+impl SourceError {
+    pub(crate) fn io(path: impl Into<PathBuf>, error: io::Error) -> Self {
+        SourceError::Io {
+            path: Some(path.into()),
+            error,
+        }
+    }
+}
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io {
+                path: Some(path),
+                error,
+            } => write!(f, "Failed to access {}: {error}", path.display()),
+            SourceError::Io { path: None, error } => write!(f, "Failed to read source: {error}"),
+            SourceError::MalformedXml {
+                line,
+                column,
+                message,
+            } => write!(f, "Malformed XML at line {line}, column {column}: {message}"),
+            SourceError::MalformedJson {
+                line,
+                column,
+                message,
+            } => write!(f, "Malformed JSON at line {line}, column {column}: {message}"),
+            SourceError::UnexpectedStructure(message) => {
+                write!(f, "Unexpected structure: {message}")
+            }
+            SourceError::Unsupported(message) => write!(f, "Unsupported source: {message}"),
+            SourceError::UnknownSource(name) => write!(f, "Unknown source: {name}"),
+            SourceError::ReferenceNotFound { missing } => {
+                let missing = missing
+                    .iter()
+                    .map(Missing::to_string)
+                    .collect::<Vec<_>>();
+                write!(f, "Not found: {}", missing.join(", "))
+            }
+            SourceError::RangeExceedsChapter {
+                number_from,
+                number_to,
+                last_number,
+                ..
+            } => write!(f, "Incorrect range: range has verses from {number_from} to {number_to}, but there are only verses up to {last_number} in the given chapter."),
+        }
+    }
+}
+impl Error for SourceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SourceError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<xml::reader::Error> for SourceError {
+    fn from(error: xml::reader::Error) -> Self {
+        let position = error.position();
+        SourceError::MalformedXml {
+            line: position.row + 1,
+            column: position.column + 1,
+            message: error.msg().to_string(),
+        }
+    }
+}
+impl From<serde_json::Error> for SourceError {
+    fn from(error: serde_json::Error) -> Self {
+        // The message of a JSON error ends with its position, which is stored separately.
+        let message = error.to_string();
+        let message = message.split(" at line ").next().unwrap_or_default();
+        SourceError::MalformedJson {
+            line: error.line(),
+            column: error.column(),
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    references_of_verses, Book, Heading, Reference, ReferenceParseResult, Source, SourceError,
+    SourceMetadata, StrongsNumber, VerseContent, VerseId, VerseIdRange,
+};
+
+/// Index of verses in canonical order for fast lookups.
+///
+/// A verse is found by its ID and a chapter by its book and number in constant time,
+/// and a range of verses by binary search.
+#[derive(Clone, Debug, Default)]
+pub struct VerseIndex {
+    /// Verses sorted by their IDs.
+    verses: Vec<(VerseId, VerseContent)>,
+    /// Position of each verse in `verses`.
+    positions: HashMap<VerseId, usize>,
+    /// Range of positions in `verses` of each chapter.
+    chapters: HashMap<(Book, u8), Range<usize>>,
+}
+impl VerseIndex {
+    /// Creates an index of the given verses, which are sorted into canonical order.
+    /// If a verse occurs many times, only its first occurrence is kept.
+    pub fn new(mut verses: Vec<(VerseId, VerseContent)>) -> Self {
+        // Sorting is stable, so first occurrences remain first.
+        verses.sort_by_key(|(id, _)| *id);
+        verses.dedup_by_key(|(id, _)| *id);
+
+        let mut positions = HashMap::with_capacity(verses.len());
+        let mut chapters: HashMap<(Book, u8), Range<usize>> = HashMap::new();
+        for (position, (id, _)) in verses.iter().enumerate() {
+            positions.insert(*id, position);
+            chapters
+                .entry((id.book(), id.chapter()))
+                .and_modify(|chapter| chapter.end = position + 1)
+                .or_insert(position..position + 1);
+        }
+
+        Self {
+            verses,
+            positions,
+            chapters,
+        }
+    }
+
+    /// Returns the content of a verse.
+    pub fn get(&self, id: VerseId) -> Option<&VerseContent> {
+        self.positions
+            .get(&id)
+            .map(|&position| &self.verses[position].1)
+    }
+    /// Returns the verses of a chapter, which is empty if the chapter is not in the index.
+    pub fn chapter(&self, book: Book, chapter: u8) -> &[(VerseId, VerseContent)] {
+        self.chapters
+            .get(&(book, chapter))
+            .map_or(&[], |range| &self.verses[range.clone()])
+    }
+    /// Returns the verses whose IDs are within the given range.
+    pub fn range(&self, range: VerseIdRange) -> &[(VerseId, VerseContent)] {
+        let start = self.verses.partition_point(|(id, _)| *id < range.start());
+        let end = self.verses.partition_point(|(id, _)| *id <= range.end());
+        &self.verses[start..end]
+    }
+    /// Returns all verses in canonical order.
+    pub fn verses(&self) -> &[(VerseId, VerseContent)] {
+        &self.verses
+    }
+    pub fn len(&self) -> usize {
+        self.verses.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.verses.is_empty()
+    }
+}
+
+/// Source of Bible content with all verses in memory, indexed for fast lookups.
+///
+/// It is created by parsing a source once, e.g. with [`crate::OsisSource::index`],
+/// after which lookups don't parse anything.
+#[derive(Clone, Debug, Default)]
+pub struct IndexedSource {
+    index: VerseIndex,
+    /// Headings sorted by the verses they stand before.
+    headings: Vec<Heading>,
+    introductions: HashMap<Book, String>,
+    metadata: SourceMetadata,
+}
+impl Source for IndexedSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        let Some(bounds) = parse_result.verse_id_bounds() else {
+            return Ok(vec![]);
+        };
+        references_of_verses(parse_result, self.index.range(bounds).iter().cloned())
+    }
+    fn metadata(&self) -> &SourceMetadata {
+        &self.metadata
+    }
+}
+impl IndexedSource {
+    /// Creates a source of indexed verses, their headings and the introductions of books.
+    /// If a book has many introductions, only the first one is kept.
+    pub fn new(
+        index: VerseIndex,
+        mut headings: Vec<Heading>,
+        introductions: Vec<(Book, String)>,
+    ) -> Self {
+        headings.sort_by_key(|heading| heading.verse_id);
+        let mut introductions_by_book = HashMap::new();
+        for (book, text) in introductions {
+            introductions_by_book.entry(book).or_insert(text);
+        }
+        Self {
+            index,
+            headings,
+            introductions: introductions_by_book,
+            metadata: SourceMetadata::default(),
+        }
+    }
+    /// Sets the metadata of the source, e.g. as read from the header of its file.
+    pub fn with_metadata(mut self, metadata: SourceMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn index(&self) -> &VerseIndex {
+        &self.index
+    }
+    pub(crate) fn headings(&self) -> &[Heading] {
+        &self.headings
+    }
+    pub(crate) fn introductions(&self) -> &HashMap<Book, String> {
+        &self.introductions
+    }
+    /// Finds every verse with a word tagged with the given Strong's number, in canonical order.
+    pub fn find_verses_with_strongs(
+        &self,
+        strongs: StrongsNumber,
+    ) -> Vec<(VerseId, &VerseContent)> {
+        self.index
+            .verses()
+            .iter()
+            .filter(|(_, content)| content.contains_strongs(strongs))
+            .map(|(id, content)| (*id, content))
+            .collect()
+    }
+    /// Finds headings that stand before or within the referenced verses, in canonical order.
+    pub fn find_headings(&self, parse_result: &ReferenceParseResult) -> &[Heading] {
+        find_headings(&self.headings, parse_result)
+    }
+    /// Finds the title of a Psalm that is part of the original text, e.g. "A Psalm of David."
+    pub fn find_psalm_title(&self, chapter: u8) -> Option<&str> {
+        find_psalm_title(&self.headings, chapter)
+    }
+    /// Finds the introduction of a book.
+    pub fn find_introduction(&self, book: Book) -> Option<&str> {
+        self.introductions.get(&book).map(String::as_str)
+    }
+}
+
+/// Finds the headings, in canonical order, that stand before or within the referenced verses.
+pub(crate) fn find_headings<'a>(
+    headings: &'a [Heading],
+    parse_result: &ReferenceParseResult,
+) -> &'a [Heading] {
+    let Some(bounds) = parse_result.verse_id_bounds() else {
+        return &[];
+    };
+    let start = headings.partition_point(|heading| heading.verse_id < bounds.start());
+    let end = headings.partition_point(|heading| heading.verse_id <= bounds.end());
+    &headings[start..end]
+}
+/// Finds the title of a Psalm among headings in canonical order.
+pub(crate) fn find_psalm_title(headings: &[Heading], chapter: u8) -> Option<&str> {
+    let first_verse = VerseId::new(Book::Psalms, chapter, 1);
+    headings
+        .iter()
+        .skip_while(|heading| heading.verse_id < first_verse)
+        .take_while(|heading| heading.verse_id == first_verse)
+        .find(|heading| heading.is_psalm_title())
+        .map(|heading| heading.text.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_reference, HeadingType};
+
+    fn verse(book: Book, chapter: u8, verse: u8, text: &str) -> (VerseId, VerseContent) {
+        (VerseId::new(book, chapter, verse), VerseContent::from(text))
+    }
+
+    #[test]
+    fn look_up_verses_chapters_and_ranges() {
+        let index = VerseIndex::new(vec![
+            verse(Book::John, 3, 17, "For God sent not his Son"),
+            verse(Book::John, 3, 16, "For God so loved the world"),
+            verse(Book::John, 4, 1, "When therefore the Lord knew"),
+            verse(Book::John, 3, 16, "Duplicate"),
+        ]);
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            index
+                .get(VerseId::new(Book::John, 3, 16))
+                .map(VerseContent::plain_text)
+                .as_deref(),
+            Some("For God so loved the world")
+        );
+        assert_eq!(index.get(VerseId::new(Book::John, 3, 18)), None);
+        assert_eq!(index.chapter(Book::John, 3).len(), 2);
+        assert!(index.chapter(Book::John, 5).is_empty());
+        assert_eq!(
+            index
+                .range(
+                    VerseIdRange::new(
+                        VerseId::new(Book::John, 3, 17),
+                        VerseId::new(Book::John, 10, 1)
+                    )
+                    .unwrap()
+                )
+                .iter()
+                .map(|(id, _)| id.to_string())
+                .collect::<Vec<_>>(),
+            ["John.3.17", "John.4.1"]
+        );
+    }
+    #[test]
+    fn find_content_in_indexed_source() {
+        let source = IndexedSource::new(
+            VerseIndex::new(vec![
+                verse(
+                    Book::Jude,
+                    1,
+                    24,
+                    "Now unto him that is able to keep you from falling",
+                ),
+                verse(Book::Jude, 1, 25, "To the only wise God our Saviour"),
+            ]),
+            vec![],
+            vec![],
+        );
+        let references = source
+            .find_content(&parse_reference("Jude 1:24+").unwrap())
+            .unwrap();
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| reference.number)
+                .collect::<Vec<_>>(),
+            [24, 25]
+        );
+        assert!(matches!(
+            source.find_content(&parse_reference("Jude 1:24-26").unwrap()),
+            Err(SourceError::RangeExceedsChapter {
+                last_number: 25,
+                ..
+            })
+        ));
+    }
+    #[test]
+    fn find_psalm_titles_only_among_headings() {
+        let heading = |heading_type, chapter, text: &str| Heading {
+            heading_type,
+            canonical: true,
+            text: text.to_string(),
+            verse_id: VerseId::new(Book::Psalms, chapter, 1),
+        };
+        let source = IndexedSource::new(
+            VerseIndex::new(vec![]),
+            vec![
+                heading(HeadingType::Psalm, 3, "A Psalm of David."),
+                heading(HeadingType::Section, 119, "ALEPH."),
+            ],
+            vec![],
+        );
+        assert_eq!(source.find_psalm_title(3), Some("A Psalm of David."));
+        assert_eq!(source.find_psalm_title(119), None);
+    }
+}
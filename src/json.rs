@@ -0,0 +1,261 @@
+use std::{fs::File, io::Read};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    delimited::parse_number, Book, IndexedSource, Locale, Reference, ReferenceParseResult, Source,
+    SourceError, VerseContent, VerseId, VerseIndex,
+};
+
+/// Keys that may identify the book of a book object or a verse object.
+const BOOK_KEYS: [&str; 4] = ["book", "name", "id", "abbrev"];
+
+/// Represents a source of Bible content in JSON.
+///
+/// Two layouts are accepted:
+/// - Nested books, chapters and verses, e.g.
+///   `[{"name": "Genesis", "chapters": [["In the beginning...", "And the earth..."]]}]`.
+///   Chapters and verses may be arrays, where positions give their numbers, objects keyed by
+///   their numbers, e.g. `{"Genesis": {"1": {"1": "In the beginning..."}}}`, or arrays of objects
+///   with numbers, e.g. `{"chapter": 1, "verses": [{"verse": 1, "text": "In the beginning..."}]}`.
+/// - A flat list of verses, e.g. `[{"book": "Gen", "chapter": 1, "verse": 1, "text": "In the beginning..."}]`.
+///
+/// Either layout may also be wrapped in an object under the key "books" or "verses".
+///
+/// Books may be identified by their names, abbreviations or OSIS book IDs,
+/// e.g. "Genesis", "Gen." or "1John", or by their numbers in canonical order,
+/// 1 being Genesis and 66 Revelation. Numbers may be given as JSON numbers or strings.
+///
+/// The whole source is parsed when created.
+pub struct JsonSource {
+    source: IndexedSource,
+}
+impl Source for JsonSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+}
+impl JsonSource {
+    pub fn from_file(mut file: File) -> Result<Self, SourceError> {
+        let mut data = vec![];
+        file.read_to_end(&mut data)
+            .map_err(|error| SourceError::Io { path: None, error })?;
+        Self::from_data(&data)
+    }
+    /// Creates a source from JSON content already in memory.
+    pub fn from_data(data: &[u8]) -> Result<Self, SourceError> {
+        let value = serde_json::from_slice::<Value>(data)?;
+        let mut verses = vec![];
+        read_root(&value, &mut verses).map_err(SourceError::UnexpectedStructure)?;
+        Ok(Self {
+            source: IndexedSource::new(VerseIndex::new(verses), vec![], vec![]),
+        })
+    }
+
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+}
+
+type Verses = Vec<(VerseId, VerseContent)>;
+
+fn read_root(value: &Value, verses: &mut Verses) -> Result<(), String> {
+    match value {
+        Value::Array(entries) => {
+            for entry in entries {
+                let entry = entry
+                    .as_object()
+                    .ok_or("Expected an object for each book or verse")?;
+                if entry.contains_key("text") {
+                    read_flat_verse(entry, verses)?;
+                } else {
+                    let book = read_book_key(entry)?;
+                    let chapters = entry
+                        .get("chapters")
+                        .ok_or_else(|| format!("Expected chapters for {}", book.osis_id()))?;
+                    read_chapters(book, chapters, verses)?;
+                }
+            }
+            Ok(())
+        }
+        Value::Object(entries) => {
+            if let Some(wrapped) = entries.get("books").or_else(|| entries.get("verses")) {
+                return read_root(wrapped, verses);
+            }
+            for (book_name, chapters) in entries {
+                let book = read_book(&Value::String(book_name.clone()))?;
+                read_chapters(book, chapters, verses)?;
+            }
+            Ok(())
+        }
+        _ => Err("Expected an array or an object of books or verses".to_string()),
+    }
+}
+fn read_flat_verse(entry: &Map<String, Value>, verses: &mut Verses) -> Result<(), String> {
+    let book = read_book_key(entry)?;
+    let chapter = read_number(entry.get("chapter"), "chapter")?;
+    let verse = read_number(entry.get("verse"), "verse")?;
+    let text = read_text(entry.get("text"))?;
+    verses.push((VerseId::new(book, chapter, verse), text));
+    Ok(())
+}
+fn read_chapters(book: Book, chapters: &Value, verses: &mut Verses) -> Result<(), String> {
+    let chapters = numbered_entries(chapters, "chapter", "verses")
+        .map_err(|err| format!("{}: {err}", book.osis_id()))?;
+    for (chapter, chapter_verses) in chapters {
+        let chapter_verses = numbered_entries(chapter_verses, "verse", "text")
+            .map_err(|err| format!("{} {chapter}: {err}", book.osis_id()))?;
+        for (verse, text) in chapter_verses {
+            let text = read_text(Some(text))
+                .map_err(|err| format!("{} {chapter}:{verse}: {err}", book.osis_id()))?;
+            verses.push((VerseId::new(book, chapter, verse), text));
+        }
+    }
+    Ok(())
+}
+/// Returns the numbers and contents of chapters or verses in any of the nested layouts:
+/// - an array with the contents, where positions give the numbers,
+/// - an object with the numbers as keys,
+/// - an array of objects with the number and content under the given keys.
+fn numbered_entries<'a>(
+    value: &'a Value,
+    number_key: &str,
+    content_key: &str,
+) -> Result<Vec<(u8, &'a Value)>, String> {
+    match value {
+        Value::Array(entries) => entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| match entry.as_object() {
+                Some(object) if object.contains_key(number_key) => Ok((
+                    read_number(object.get(number_key), number_key)?,
+                    object.get(content_key).unwrap_or(&Value::Null),
+                )),
+                _ => Ok((
+                    u8::try_from(index + 1).map_err(|_| format!("Too many {number_key}s"))?,
+                    entry,
+                )),
+            })
+            .collect(),
+        Value::Object(entries) => entries
+            .iter()
+            .map(|(number, entry)| Ok((parse_number(number, number_key)?, entry)))
+            .collect(),
+        _ => Err(format!("Expected an array or an object of {number_key}s")),
+    }
+}
+
+fn read_book_key(entry: &Map<String, Value>) -> Result<Book, String> {
+    let value = BOOK_KEYS
+        .iter()
+        .find_map(|key| entry.get(*key))
+        .ok_or("Expected a book name or number")?;
+    read_book(value)
+}
+fn read_book(value: &Value) -> Result<Book, String> {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .and_then(|number| u8::try_from(number).ok())
+            .and_then(Book::from_number),
+        Value::String(name) => match name.trim().parse::<u8>() {
+            Ok(number) => Book::from_number(number),
+            Err(_) => Book::from_name(name.trim(), Locale::En),
+        },
+        _ => None,
+    }
+    .ok_or_else(|| format!("Unknown book: {value}"))
+}
+fn read_number(value: Option<&Value>, name: &str) -> Result<u8, String> {
+    match value {
+        Some(Value::Number(number)) => number
+            .as_u64()
+            .and_then(|number| u8::try_from(number).ok())
+            .filter(|number| *number > 0)
+            .ok_or_else(|| format!("Invalid {name} number: {number}")),
+        Some(Value::String(number)) => parse_number(number, name),
+        _ => Err(format!("Expected a {name} number")),
+    }
+}
+fn read_text(value: Option<&Value>) -> Result<VerseContent, String> {
+    match value {
+        Some(Value::String(text)) => Ok(VerseContent::from(text.trim())),
+        _ => Err("Expected verse text".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_reference;
+
+    fn plain_texts(source: &JsonSource, reference: &str) -> Vec<String> {
+        source
+            .find_content(&parse_reference(reference).unwrap())
+            .unwrap()
+            .iter()
+            .map(|reference| reference.content.plain_text())
+            .collect()
+    }
+
+    #[test]
+    fn read_nested_books_chapters_and_verses() {
+        let source = JsonSource::from_data(
+            br#"[
+                {"name": "Genesis", "chapters": [[
+                    "In the beginning God created the heaven and the earth.",
+                    "And the earth was without form, and void."
+                ]]},
+                {"book": 43, "chapters": [{"chapter": 11, "verses": [
+                    {"verse": 35, "text": "Jesus wept."}
+                ]}]}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "Gen 1:2"),
+            ["And the earth was without form, and void."]
+        );
+        assert_eq!(plain_texts(&source, "John 11:35"), ["Jesus wept."]);
+
+        let source = JsonSource::from_data(
+            br#"{"books": {"1John": {"4": {"8": "He that loveth not knoweth not God; for God is love."}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "1 John 4:8"),
+            ["He that loveth not knoweth not God; for God is love."]
+        );
+    }
+    #[test]
+    fn read_flat_verses() {
+        let source = JsonSource::from_data(
+            br#"{"verses": [
+                {"book": "Ps", "chapter": 23, "verse": 1, "text": "The LORD is my shepherd; I shall not want."},
+                {"book": "19", "chapter": "117", "verse": "2", "text": "For his merciful kindness is great toward us."}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            plain_texts(&source, "Psalms 23:1"),
+            ["The LORD is my shepherd; I shall not want."]
+        );
+        assert_eq!(
+            plain_texts(&source, "Psalms 117:2"),
+            ["For his merciful kindness is great toward us."]
+        );
+
+        let error =
+            JsonSource::from_data(br#"[{"book": "Jhn", "chapter": 1, "verse": 0, "text": ""}]"#)
+                .err()
+                .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected structure: Invalid verse number: 0"
+        );
+    }
+}
@@ -1,10 +1,44 @@
-use std::{
-    fs::File,
-    io::{Cursor, Read},
+pub use book::{Book, Canon, Genre, Testament, MAX_BOOK_SUGGESTIONS};
+pub use cache::CompiledSource;
+pub use content::{
+    Break, Heading, HeadingType, MorphologyCode, Note, NoteType, Segment, StrongsNumber, TextStyle,
+    VerseContent, Word,
 };
+pub use delimited::{ColumnMapping, DelimitedConfig, DelimitedSource, HeaderMode, LineLayout};
+pub use error::SourceError;
+pub use index::{IndexedSource, VerseIndex};
+pub use json::JsonSource;
+pub use library::{Library, LibraryEntry, SourceFormat};
+pub use lookup::{Lookup, Missing};
+pub use metadata::SourceMetadata;
+pub use osis::OsisSource;
+pub use parallel::{find_parallel, ParallelLookup, ParallelVerse};
+pub use sword::SwordSource;
+pub use usfm::UsfmSource;
+pub use usx::UsxSource;
+pub use verse_id::{InvalidVerseId, VerseId, VerseIdRange, VerseIdRangeIter};
+pub use zefania::ZefaniaSource;
 
-use xml::{attribute::OwnedAttribute, reader::XmlEvent};
+mod book;
+mod cache;
+mod content;
+mod delimited;
+mod error;
+mod index;
+mod json;
+mod library;
+mod lookup;
+mod metadata;
+mod osis;
+mod parallel;
+mod sword;
+mod usfm;
+mod usx;
+mod verse_id;
+mod versification;
+mod zefania;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Locale {
     En,
 }
@@ -13,7 +47,7 @@ pub enum Locale {
 pub struct Reference {
     pub chapter: u8,
     pub number: u8,
-    pub content: String,
+    pub content: VerseContent,
 }
 
 #[derive(Debug, PartialEq)]
@@ -22,434 +56,635 @@ pub struct ReferenceParseResult<'a> {
     pub chapter: u8,
     reference_type: ReferenceParseResultType,
 }
+impl ReferenceParseResult<'_> {
+    /// Returns the book the reference points to, resolved from the book name.
+    pub fn book(&self) -> Option<Book> {
+        Book::from_name(self.book_name, Locale::En)
+    }
+    /// Returns the range of verse IDs the reference covers.
+    ///
+    /// Returns `None` if the book name is unknown
+    /// or the referenced chapters or verses don't exist in the versification.
+    pub fn verse_id_range(&self) -> Option<VerseIdRange> {
+        let book = self.book()?;
+        let (chapter_to, number_from, number_to) = match self.reference_type {
+            ReferenceParseResultType::Book => {
+                let chapter_to = book.chapter_count();
+                return VerseIdRange::new(
+                    VerseId::new(book, 1, 1),
+                    VerseId::new(book, chapter_to, book.verse_count(chapter_to)?),
+                );
+            }
+            ReferenceParseResultType::Chapter => (self.chapter, 1, book.verse_count(self.chapter)?),
+            ReferenceParseResultType::ChapterFromTo { chapter_to } => {
+                (chapter_to, 1, book.verse_count(chapter_to)?)
+            }
+            ReferenceParseResultType::Verse { number } => (self.chapter, number, number),
+            ReferenceParseResultType::VerseFromOnwards { number_from } => {
+                (self.chapter, number_from, book.verse_count(self.chapter)?)
+            }
+            ReferenceParseResultType::VerseFromTo {
+                number_from,
+                number_to,
+            } => (self.chapter, number_from, number_to),
+            ReferenceParseResultType::VerseFromToAcrossChapters {
+                number_from,
+                chapter_to,
+                number_to,
+            } => (chapter_to, number_from, number_to),
+        };
+        if number_from == 0
+            || number_from > book.verse_count(self.chapter)?
+            || number_to > book.verse_count(chapter_to)?
+        {
+            return None;
+        }
+        VerseIdRange::new(
+            VerseId::new(book, self.chapter, number_from),
+            VerseId::new(book, chapter_to, number_to),
+        )
+    }
+    /// Returns bounds for the verse IDs that the reference may match in a source.
+    ///
+    /// Unlike [`ReferenceParseResult::verse_id_range`], the bounds don't depend on
+    /// the versification, since sources may have a different number of verses in a chapter.
+    /// For this reason, the end of an open-ended range is the highest possible verse ID
+    /// in the chapter or book.
+    pub(crate) fn verse_id_bounds(&self) -> Option<VerseIdRange> {
+        let book = self.book()?;
+        let (chapter_to, number_from, number_to) = match self.reference_type {
+            ReferenceParseResultType::Book => (u8::MAX, 1, u8::MAX),
+            ReferenceParseResultType::Chapter => (self.chapter, 1, u8::MAX),
+            ReferenceParseResultType::ChapterFromTo { chapter_to } => (chapter_to, 1, u8::MAX),
+            ReferenceParseResultType::Verse { number } => (self.chapter, number, number),
+            ReferenceParseResultType::VerseFromOnwards { number_from } => {
+                (self.chapter, number_from, u8::MAX)
+            }
+            ReferenceParseResultType::VerseFromTo {
+                number_from,
+                number_to,
+            } => (self.chapter, number_from, number_to),
+            ReferenceParseResultType::VerseFromToAcrossChapters {
+                number_from,
+                chapter_to,
+                number_to,
+            } => (chapter_to, number_from, number_to),
+        };
+        let chapter_from = match self.reference_type {
+            ReferenceParseResultType::Book => 1,
+            _ => self.chapter,
+        };
+        VerseIdRange::new(
+            VerseId::new(book, chapter_from, number_from),
+            VerseId::new(book, chapter_to, number_to),
+        )
+    }
+    /// Returns an iterator that expands the reference into its individual verses.
+    ///
+    /// Returns `None` in the same cases as [`ReferenceParseResult::verse_id_range`].
+    pub fn verses(&self) -> Option<VerseIdRangeIter> {
+        self.verse_id_range().map(|range| range.iter())
+    }
+    /// Returns the number of verses the reference covers without loading any text.
+    ///
+    /// Returns `None` in the same cases as [`ReferenceParseResult::verse_id_range`].
+    pub fn verse_count(&self) -> Option<usize> {
+        self.verse_id_range().and_then(|range| range.verse_count())
+    }
+}
 #[derive(Debug, PartialEq)]
 pub enum ReferenceParseResultType {
     /// Bible verse reference to a chapter.
     Chapter,
+    /// Bible verse reference to a range of whole chapters from `chapter` to `chapter_to`.
+    ChapterFromTo { chapter_to: u8 },
     /// Bible verse reference to a verse.
     Verse { number: u8 },
     /// Bible verse reference to a verse and all verses onwards in a chapter.
     VerseFromOnwards { number_from: u8 },
     /// Bible verse reference to a range of verses.
     VerseFromTo { number_from: u8, number_to: u8 },
+    /// Bible verse reference to a range of verses that continues from one chapter to another.
+    /// The range starts from a verse in `chapter` and ends to a verse in `chapter_to`.
+    VerseFromToAcrossChapters {
+        number_from: u8,
+        chapter_to: u8,
+        number_to: u8,
+    },
+    /// Bible verse reference to a whole book. The chapter of the reference is 0.
+    Book,
 }
 #[derive(Debug, PartialEq)]
 pub enum ReferenceParseErrorCode {
+    /// A range between bare numbers (e.g. "John 3-5") in strict mode,
+    /// which could mean either chapters or verses.
+    AmbiguousRange,
     BookNameNeverEnds,
+    /// A book name that is not capitalized conventionally in strict mode.
+    InvalidBookNameFormat,
     InvalidChapterFormat,
     InvalidChapterValue,
+    InvalidRangeBetweenChapters,
     InvalidRangeBetweenVerseNumbers,
     InvalidVerseNumberFormat,
+    /// Whitespace where it is not allowed in strict mode, or missing where it is required.
+    InvalidWhitespace,
+    /// Characters after a complete reference, other than trailing punctuation in lenient mode.
+    UnexpectedTrailingCharacters,
+    /// The book name matches no known book. Contains books with similar names, if any,
+    /// ranked from the most to the least similar.
+    UnknownBookName {
+        suggestions: Vec<Book>,
+    },
     UnknownError,
 }
 impl ReferenceParseErrorCode {
     pub fn to_string(&self, locale: Locale) -> &'static str {
         match locale {
             Locale::En => match self {
+                ReferenceParseErrorCode::AmbiguousRange => {
+                    "Ambiguous range: use \"chapter:verse\" to refer to verses."
+                }
                 ReferenceParseErrorCode::BookNameNeverEnds => "Book name never ends.",
+                ReferenceParseErrorCode::InvalidBookNameFormat => "Invalid book name format.",
                 ReferenceParseErrorCode::InvalidChapterFormat => "Invalid chapter format.",
                 ReferenceParseErrorCode::InvalidChapterValue => {
                     "Invalid value for a chapter given."
                 }
+                ReferenceParseErrorCode::InvalidRangeBetweenChapters => {
+                    "Invalid range between chapters."
+                }
                 ReferenceParseErrorCode::InvalidRangeBetweenVerseNumbers => {
                     "Invalid range between verse numbers."
                 }
                 ReferenceParseErrorCode::InvalidVerseNumberFormat => "Invalid verse number format.",
+                ReferenceParseErrorCode::InvalidWhitespace => "Invalid whitespace.",
+                ReferenceParseErrorCode::UnexpectedTrailingCharacters => {
+                    "Unexpected characters after the reference."
+                }
+                ReferenceParseErrorCode::UnknownBookName { .. } => "Unknown book name.",
                 ReferenceParseErrorCode::UnknownError => "Unknown error.",
             },
         }
     }
+    /// Returns suggested books for an unknown book name or an empty slice for other errors.
+    pub fn suggestions(&self) -> &[Book] {
+        match self {
+            ReferenceParseErrorCode::UnknownBookName { suggestions } => suggestions,
+            _ => &[],
+        }
+    }
+}
+
+/// Converts verses found for a reference into references,
+/// ensuring that a range of verses doesn't continue past the end of the chapter.
+pub(crate) fn references_of_verses(
+    parse_result: &ReferenceParseResult,
+    verses: impl IntoIterator<Item = (VerseId, VerseContent)>,
+) -> Result<Vec<Reference>, SourceError> {
+    let verse_references: Vec<Reference> = verses
+        .into_iter()
+        .map(|(id, content)| Reference {
+            chapter: id.chapter(),
+            number: id.verse(),
+            content,
+        })
+        .collect();
+
+    if let ReferenceParseResultType::VerseFromTo {
+        number_from,
+        number_to,
+    } = parse_result.reference_type
+    {
+        if let Some(last_reference) = verse_references.last() {
+            if last_reference.number < number_to {
+                return Err(SourceError::RangeExceedsChapter {
+                    chapter: last_reference.chapter,
+                    number_from,
+                    number_to,
+                    last_number: last_reference.number,
+                });
+            }
+        }
+    }
+
+    Ok(verse_references)
 }
 
 pub trait Source {
-    fn find_content(&self, parse_result: &ReferenceParseResult) -> Result<Vec<Reference>, String>;
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError>;
+
+    /// Finds only the words of Christ, i.e. the red-letter text, of the referenced verses.
+    /// Verses without any words of Christ are left out.
+    fn find_words_of_christ(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        Ok(self
+            .find_content(parse_result)?
+            .into_iter()
+            .map(|reference| Reference {
+                content: reference.content.retain_words_of_christ(),
+                ..reference
+            })
+            .filter(|reference| !reference.content.is_empty())
+            .collect())
+    }
+    /// Finds the content of the referenced verses like [`Source::find_content`],
+    /// and reports which of the referenced books, chapters and verses the source doesn't have.
+    ///
+    /// A range of verses that ends past the last verse of its chapter in the source is not
+    /// an error here. Instead, the verses past the end are reported missing.
+    fn look_up(&self, parse_result: &ReferenceParseResult) -> Result<Lookup, SourceError> {
+        let references = match self.find_content(parse_result) {
+            Err(SourceError::RangeExceedsChapter { number_from, .. }) => {
+                // The chapter ends within the range, so the rest of the chapter
+                // has the same verses as the range.
+                self.find_content(&ReferenceParseResult {
+                    book_name: parse_result.book_name,
+                    chapter: parse_result.chapter,
+                    reference_type: ReferenceParseResultType::VerseFromOnwards { number_from },
+                })?
+            }
+            result => result?,
+        };
+        Ok(Lookup::new(parse_result, references))
+    }
+    /// Returns the metadata of the source, e.g. its title, language and rights.
+    /// It is empty if the format of the source doesn't carry any.
+    fn metadata(&self) -> &SourceMetadata {
+        &metadata::EMPTY_METADATA
+    }
 }
-/// Represents a source of Bible content according to Open Scripture Information Standard (OSIS).
-/// It is stored in an XML format.
-///
-/// See https://en.wikipedia.org/wiki/Open_Scripture_Information_Standard for more information.
-pub struct OsisSource {
-    data: Vec<u8>,
+/// Mode that determines how forgiving parsing is of the format of a reference.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParseMode {
+    /// The whole input must be a reference in the conventional format, e.g. "John 3:16" or
+    /// "1 John 3:16-4:2", and anything ambiguous is an error:
+    /// - The book name is capitalized and separated from the chapter by exactly one space.
+    ///   A book number is followed by at most one space, e.g. "1 John" or "1John".
+    /// - A book name of many words must be a known name, e.g. "Song of Solomon",
+    ///   so that text like "John foo" is rejected.
+    /// - There is no other whitespace and nothing after the reference.
+    /// - A range of bare numbers like "John 3-5" is rejected, as it could mean either chapters
+    ///   or verses.
+    ///
+    /// Suitable for validating user input.
+    Strict,
+    /// Tolerates common variations in the format, while still rejecting
+    /// anything after the reference that is not punctuation:
+    /// - Extra or missing whitespace, e.g. "1Jn3:16" or "John 3 : 16".
+    /// - Book names in any letter case, e.g. "JOHN 3:16".
+    /// - Periods as chapter and verse separators and dashes as range separators, e.g. "John 3.16–18".
+    /// - Trailing punctuation, e.g. "John 3:16.".
+    /// - Ranges of bare numbers like "John 3-5" as chapter ranges.
+    ///
+    /// Suitable for importing messy data.
+    #[default]
+    Lenient,
+}
+
+/// Configuration for parsing Bible references.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ParserConfig {
+    pub mode: ParseMode,
+    /// Whether to fail with [`ReferenceParseErrorCode::UnknownBookName`] if the book name
+    /// is not a known English name. Off by default, so that names in other languages parse.
+    pub require_known_book: bool,
 }
-impl Source for OsisSource {
-    fn find_content(&self, parse_result: &ReferenceParseResult) -> Result<Vec<Reference>, String> {
-        fn matches_xml_attribute(attribute: &&OwnedAttribute, name: &str, value: &str) -> bool {
-            attribute.name.local_name == name && attribute.value == value
+impl ParserConfig {
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+            ..Self::default()
         }
+    }
+    pub fn lenient() -> Self {
+        Self {
+            mode: ParseMode::Lenient,
+            ..Self::default()
+        }
+    }
+}
 
-        let file_reader = Cursor::new(&self.data);
-        let mut parser = xml::EventReader::new(file_reader);
-
-        match parse_result.reference_type {
-            ReferenceParseResultType::Chapter => {
-                let mut reading_chapter = false;
-                let mut verse_references = Vec::<Reference>::new();
-                let mut verse_count = 0u8;
-
-                // XML element traversal operates in line with the following assumptions:
-                // - The XML content is valid.
-                // - Each <chapter> element has only <verse> elements as children in numerical order.
-                // - Each <verse> element has text content only.
-                while let Ok(element) = parser.next() {
-                    if let XmlEvent::StartElement {
-                        name, attributes, ..
-                    } = element
-                    {
-                        if name.local_name == "chapter"
-                            && attributes
-                                .iter()
-                                .find(|attribute| {
-                                    matches_xml_attribute(
-                                        attribute,
-                                        "osisID",
-                                        &format!(
-                                            "{}.{}",
-                                            parse_result.book_name, parse_result.chapter
-                                        ),
-                                    )
-                                })
-                                .is_some()
-                        {
-                            reading_chapter = true;
-                        } else if reading_chapter && name.local_name == "verse" {
-                            if let Ok(XmlEvent::Characters(content)) = parser.next() {
-                                verse_count += 1;
-                                verse_references.push(Reference {
-                                    chapter: parse_result.chapter,
-                                    number: verse_count,
-                                    content,
-                                });
-                            } else {
-                                return Err(String::from("Failed to parse verse content. Expected verse content to follow verse start element."));
-                            }
-                        }
-                    } else if let XmlEvent::EndElement { name } = element {
-                        if name.local_name == "chapter" && reading_chapter {
-                            break;
-                        }
-                    } else if let XmlEvent::EndDocument = element {
-                        break;
-                    }
-                }
+/// Characters that lenient parsing ignores at the end of a reference.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']'];
 
-                Ok(verse_references)
-            }
-            ReferenceParseResultType::Verse { number } => {
-                // XML element traversal operates in line with the following assumptions:
-                // - The XML content is valid.
-                // - Each <chapter> element has only <verse> elements as children in numerical order.
-                // - Each <verse> element has text content only.
-                while let Ok(element) = parser.next() {
-                    if let XmlEvent::StartElement {
-                        name, attributes, ..
-                    } = element
-                    {
-                        if name.local_name == "verse" {
-                            if let Some(_) = attributes.iter().find(|attribute| {
-                                matches_xml_attribute(
-                                    attribute,
-                                    "osisID",
-                                    &format!(
-                                        "{}.{}.{}",
-                                        parse_result.book_name, parse_result.chapter, number
-                                    ),
-                                )
-                            }) {
-                                if let Ok(XmlEvent::Characters(content)) = parser.next() {
-                                    return Ok(vec![Reference {
-                                        chapter: parse_result.chapter,
-                                        number,
-                                        content,
-                                    }]);
-                                } else {
-                                    return Err(String::from("Failed to parse verse content. Expected verse content to follow verse start element."));
-                                }
-                            }
-                        }
-                    } else if let XmlEvent::EndDocument = element {
-                        break;
-                    }
-                }
-                Ok(vec![])
+/// Cursor over a reference string, tracking the byte position of the next character.
+struct ReferenceScanner<'a> {
+    value: &'a str,
+    position: usize,
+}
+impl<'a> ReferenceScanner<'a> {
+    fn is_at_end(&self) -> bool {
+        self.position == self.value.len()
+    }
+    fn peek(&self) -> Option<char> {
+        self.value[self.position..].chars().next()
+    }
+    fn peek_second(&self) -> Option<char> {
+        self.value[self.position..].chars().nth(1)
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+    /// Advances past characters as long as they match the predicate and returns them.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.next();
+        }
+        &self.value[start..self.position]
+    }
+    /// Advances past whitespace, but only in lenient mode.
+    fn skip_lenient_whitespace(&mut self, mode: ParseMode) {
+        if mode == ParseMode::Lenient {
+            self.take_while(char::is_whitespace);
+        }
+    }
+    /// Advances past a chapter and verse separator (:) if one is next.
+    fn skip_chapter_separator(&mut self, mode: ParseMode) -> bool {
+        let is_separator = match self.peek() {
+            // In lenient mode, the colon of "John 3:" is just trailing punctuation.
+            Some(':') => mode == ParseMode::Strict || !self.is_at_trailing_punctuation(),
+            // In "John 3.16" the period separates a chapter and a verse,
+            // but in "John 3." it is just trailing punctuation.
+            Some('.') => {
+                mode == ParseMode::Lenient && self.peek_second().is_some_and(|c| c.is_ascii_digit())
             }
-            ReferenceParseResultType::VerseFromOnwards { number_from } => {
-                let verse_reference_ids = (number_from..=u8::MAX)
-                    .map(|n| format!("{}.{}.{}", parse_result.book_name, parse_result.chapter, n))
-                    .collect::<Vec<_>>();
-
-                let mut reading_verses = false;
-                let mut verse_references =
-                    Vec::<Reference>::with_capacity(verse_reference_ids.len());
-                let mut verse_references_handled_count = 0;
-
-                // XML element traversal operates in line with the following assumptions:
-                // - The XML content is valid.
-                // - Each <chapter> element has only <verse> elements as children in numerical order.
-                // - Each <verse> element has text content only.
-                while let Ok(element) = parser.next() {
-                    if let XmlEvent::StartElement {
-                        name, attributes, ..
-                    } = element
-                    {
-                        if name.local_name == "verse" {
-                            // Expect all verses to be in numerical order.
-                            if let Some(_) = attributes.iter().find(|attribute| {
-                                matches_xml_attribute(
-                                    attribute,
-                                    "osisID",
-                                    &verse_reference_ids[verse_references_handled_count],
-                                )
-                            }) {
-                                if let Ok(XmlEvent::Characters(content)) = parser.next() {
-                                    verse_references.push(Reference {
-                                        chapter: parse_result.chapter,
-                                        number: number_from + verse_references_handled_count as u8,
-                                        content,
-                                    });
-                                } else {
-                                    return Err(String::from("Failed to parse verse content. Expected verse content to follow verse start element."));
-                                }
-
-                                reading_verses = true;
-                                verse_references_handled_count += 1;
-                            }
-                        }
-                        // If still reading verses and encountered a start element that is not a verse
-                        else if reading_verses {
-                            break;
-                        }
-                    } else if let XmlEvent::EndDocument = element {
-                        break;
-                    }
-                }
+            _ => false,
+        };
+        if is_separator {
+            self.next();
+        }
+        is_separator
+    }
+    /// Advances past a range separator (-) if one is next.
+    fn skip_range_separator(&mut self, mode: ParseMode) -> bool {
+        let is_separator = match self.peek() {
+            Some('-') => true,
+            Some('‐' | '–' | '—') => mode == ParseMode::Lenient,
+            _ => false,
+        };
+        if is_separator {
+            self.next();
+        }
+        is_separator
+    }
+    fn parse_number(
+        &mut self,
+        error: ReferenceParseErrorCode,
+    ) -> Result<u8, ReferenceParseErrorCode> {
+        self.take_while(|c| c.is_ascii_digit())
+            .parse::<u8>()
+            .map_err(|_| error)
+    }
+    /// Returns whether only whitespace and trailing punctuation follow.
+    fn is_at_trailing_punctuation(&self) -> bool {
+        self.value[self.position..]
+            .chars()
+            .all(|c| c.is_whitespace() || TRAILING_PUNCTUATION.contains(&c))
+    }
+    /// Ensures that nothing but, in lenient mode, whitespace and punctuation follows.
+    fn finish(&mut self, mode: ParseMode) -> Result<(), ReferenceParseErrorCode> {
+        if mode == ParseMode::Lenient {
+            self.take_while(|c| c.is_whitespace() || TRAILING_PUNCTUATION.contains(&c));
+        }
+        if !self.is_at_end() {
+            return Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters);
+        }
+        Ok(())
+    }
+}
 
-                Ok(verse_references)
-            }
-            ReferenceParseResultType::VerseFromTo {
-                number_from,
-                number_to,
-            } => {
-                let verse_reference_ids = (number_from..=number_to)
-                    .map(|n| format!("{}.{}.{}", parse_result.book_name, parse_result.chapter, n))
-                    .collect::<Vec<_>>();
-
-                let mut reading_verses = false;
-                let mut verse_references =
-                    Vec::<Reference>::with_capacity(verse_reference_ids.len());
-                let mut verse_references_handled_count = 0;
-
-                // XML element traversal operates in line with the following assumptions:
-                // - The XML content is valid.
-                // - Each <chapter> element has only <verse> elements as children in numerical order.
-                // - Each <verse> element has text content only.
-                while let Ok(element) = parser.next() {
-                    if let XmlEvent::StartElement {
-                        name, attributes, ..
-                    } = element
-                    {
-                        if name.local_name == "verse" {
-                            // Expect all verses to be in numerical order.
-                            if let Some(_) = attributes.iter().find(|attribute| {
-                                matches_xml_attribute(
-                                    attribute,
-                                    "osisID",
-                                    &verse_reference_ids[verse_references_handled_count],
-                                )
-                            }) {
-                                if let Ok(XmlEvent::Characters(content)) = parser.next() {
-                                    verse_references.push(Reference {
-                                        chapter: parse_result.chapter,
-                                        number: number_from + verse_references_handled_count as u8,
-                                        content,
-                                    });
-                                } else {
-                                    return Err(String::from("Failed to parse verse content. Expected verse content to follow verse start element."));
-                                }
-
-                                reading_verses = true;
-                                verse_references_handled_count += 1;
-
-                                if verse_references_handled_count == verse_reference_ids.len() {
-                                    break;
-                                }
-                            }
-                        }
-                        // If still reading verses and encountered a start element that is not a verse
-                        else if reading_verses {
-                            return Err(format!("Incorrect range: range has verses from {number_from} to {number_to}, but there are only verses up to {verse_references_handled_count} in the given chapter."));
-                        }
-                    } else if let XmlEvent::EndDocument = element {
-                        break;
-                    }
-                }
+/// Parses a Bible reference string into a parse result object using the default configuration,
+/// which is lenient about the format and accepts any book name.
+pub fn parse_reference(value: &str) -> Result<ReferenceParseResult<'_>, ReferenceParseErrorCode> {
+    parse_reference_with_config(value, &ParserConfig::default())
+}
+/// Parses a Bible reference string into a parse result object using the given configuration.
+///
+/// If [`ParserConfig::require_known_book`] is set, fails with
+/// [`ReferenceParseErrorCode::UnknownBookName`] if the book name is not known,
+/// with suggestions of similarly named books.
+pub fn parse_reference_with_config<'a>(
+    value: &'a str,
+    config: &ParserConfig,
+) -> Result<ReferenceParseResult<'a>, ReferenceParseErrorCode> {
+    let parse_result = parse_reference_syntax(value, config.mode)?;
 
-                Ok(verse_references)
-            }
-            _ => Ok(vec![]),
+    if config.require_known_book && parse_result.book().is_none() {
+        return Err(ReferenceParseErrorCode::UnknownBookName {
+            suggestions: Book::suggestions(parse_result.book_name, Locale::En),
+        });
+    }
+    if config.mode == ParseMode::Strict {
+        if !is_book_name_capitalized(parse_result.book_name) {
+            return Err(ReferenceParseErrorCode::InvalidBookNameFormat);
+        }
+        // Words after the first one, e.g. "foo" of "John foo", are only accepted
+        // as part of a known name like "Song of Solomon".
+        if has_many_words(parse_result.book_name) && parse_result.book().is_none() {
+            return Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters);
         }
     }
+
+    Ok(parse_result)
 }
-impl OsisSource {
-    pub fn from_file(mut file: File) -> Self {
-        let mut data = vec![];
-        file.read_to_end(&mut data).unwrap();
-        Self { data }
+/// Parses the syntax of a Bible reference string without checking that the book name is known.
+fn parse_reference_syntax(
+    value: &str,
+    mode: ParseMode,
+) -> Result<ReferenceParseResult<'_>, ReferenceParseErrorCode> {
+    let mut scanner = ReferenceScanner { value, position: 0 };
+    scanner.skip_lenient_whitespace(mode);
+
+    // Expect a book name first, which may start with a book number (e.g. "1 John" or "1Jn").
+    let book_name_start = scanner.position;
+    let book_number = scanner.take_while(|c| c.is_ascii_digit());
+    let whitespace = scanner.take_while(char::is_whitespace);
+    let is_whitespace_valid =
+        whitespace.is_empty() || (!book_number.is_empty() && whitespace == " ");
+    if mode == ParseMode::Strict && !is_whitespace_valid {
+        return Err(ReferenceParseErrorCode::InvalidWhitespace);
     }
-}
+    if !scanner.peek().is_some_and(char::is_alphabetic) {
+        return Err(ReferenceParseErrorCode::UnknownError);
+    }
+    // The book name continues until a chapter number is found.
+    scanner.take_while(|c| !c.is_ascii_digit());
+    let book_name_with_whitespace = &value[book_name_start..scanner.position];
+    let book_name = book_name_with_whitespace.trim_end();
 
-const EMPTY_STRING: &str = "";
-
-/// Parses a Bible reference string into a parse result object.
-pub fn parse_reference(value: &str) -> Result<ReferenceParseResult, ReferenceParseErrorCode> {
-    let mut book_name = EMPTY_STRING;
-    let mut chapter = 0;
-    let mut number = 0;
-    let mut value_chars = value.chars().enumerate().peekable();
-
-    'value_chars_loop: while let Some((i, c)) = value_chars.next() {
-        // If a character is an alphabetic character, then expect a book name to follow it.
-        if c.is_alphabetic() {
-            while let Some((j, book_name_c)) = value_chars.peek() {
-                if book_name_c.is_digit(10) {
-                    let mut trim_offset = 0;
-                    loop {
-                        if value.chars().nth(*j - 1 - trim_offset).unwrap() == ' ' {
-                            trim_offset += 1;
-                        } else {
-                            break;
-                        }
+    // If the book name is never followed by a chapter, then the whole book is referenced.
+    if scanner.is_at_end() {
+        let book_name = match mode {
+            ParseMode::Strict if book_name != book_name_with_whitespace => {
+                return Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+            }
+            ParseMode::Strict => book_name,
+            ParseMode::Lenient => book_name
+                .trim_end_matches(|c: char| c.is_whitespace() || TRAILING_PUNCTUATION.contains(&c)),
+        };
+        return Ok(ReferenceParseResult {
+            book_name,
+            chapter: 0,
+            reference_type: ReferenceParseResultType::Book,
+        });
+    }
+    if mode == ParseMode::Strict && &book_name_with_whitespace[book_name.len()..] != " " {
+        return Err(ReferenceParseErrorCode::InvalidWhitespace);
+    }
+
+    let chapter = scanner.parse_number(ReferenceParseErrorCode::InvalidChapterFormat)?;
+    if chapter == 0 {
+        return Err(ReferenceParseErrorCode::InvalidChapterValue);
+    }
+    scanner.skip_lenient_whitespace(mode);
+
+    let reference_type = if scanner.skip_chapter_separator(mode) {
+        // If a chapter and verse number separator (:) is found, then expect a verse number.
+        scanner.skip_lenient_whitespace(mode);
+        let number = scanner.parse_number(ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
+        scanner.skip_lenient_whitespace(mode);
+
+        if scanner.peek() == Some('+') {
+            scanner.next();
+            ReferenceParseResultType::VerseFromOnwards {
+                number_from: number,
+            }
+        } else if scanner.skip_range_separator(mode) {
+            // If a range separator (-) is found, then expect an end verse number.
+            scanner.skip_lenient_whitespace(mode);
+            let end_number =
+                scanner.parse_number(ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
+            scanner.skip_lenient_whitespace(mode);
+
+            // If a chapter and verse number separator follows the end number,
+            // then the range continues to a verse in another chapter.
+            if scanner.skip_chapter_separator(mode) {
+                let chapter_to = end_number;
+                scanner.skip_lenient_whitespace(mode);
+                let number_to =
+                    scanner.parse_number(ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
+
+                // Ensure that the end chapter is greater than the start chapter.
+                if chapter_to < chapter {
+                    return Err(ReferenceParseErrorCode::InvalidRangeBetweenChapters);
+                }
+                // A range within one chapter written in full (e.g. "John 3:1-3:2")
+                // is the same as a plain verse range.
+                if chapter_to == chapter {
+                    if number_to < number {
+                        return Err(ReferenceParseErrorCode::InvalidRangeBetweenVerseNumbers);
+                    }
+                    ReferenceParseResultType::VerseFromTo {
+                        number_from: number,
+                        number_to,
                     }
-                    book_name = &value[i..*j - trim_offset];
-                    continue 'value_chars_loop;
                 } else {
-                    value_chars.next();
-                }
-            }
-            return Err(ReferenceParseErrorCode::BookNameNeverEnds);
-        } else if c.is_digit(10) {
-            // If a (verse) number is found, then expect an end verse number to follow it.
-            if number != 0 {
-                let mut end_number_str_end = 1;
-
-                'collect_number: while let Some((_, number_c)) = value_chars.peek() {
-                    if number_c.is_digit(10) {
-                        value_chars.next();
-                        end_number_str_end += 1;
-                    } else {
-                        break 'collect_number;
+                    ReferenceParseResultType::VerseFromToAcrossChapters {
+                        number_from: number,
+                        chapter_to,
+                        number_to,
                     }
                 }
-
-                let end_number_str = &value[i..i + end_number_str_end];
-                let end_number = end_number_str
-                    .parse::<u8>()
-                    .map_err(|_| ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
-
+            } else {
                 // Ensure that the end verse number is greater than the start verse number.
                 if end_number < number {
                     return Err(ReferenceParseErrorCode::InvalidRangeBetweenVerseNumbers);
                 }
-
-                return Ok(ReferenceParseResult {
-                    book_name,
-                    chapter,
-                    reference_type: ReferenceParseResultType::VerseFromTo {
-                        number_from: number,
-                        number_to: end_number,
-                    },
-                });
-            }
-            // If a chapter is already found, then expect a verse number to follow it.
-            else if chapter != 0 {
-                let mut number_str_end = 1;
-
-                'collect_number: while let Some((_, number_c)) = value_chars.peek() {
-                    if number_c.is_digit(10) {
-                        value_chars.next();
-                        number_str_end += 1;
-                    } else if *number_c == '-' {
-                        value_chars.next();
-                        let number_str = &value[i..i + number_str_end];
-                        number = number_str
-                            .parse::<u8>()
-                            .map_err(|_| ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
-                        continue 'value_chars_loop;
-                    } else if *number_c == '+' {
-                        let number_str = &value[i..i + number_str_end];
-                        let number_from = number_str
-                            .parse::<u8>()
-                            .map_err(|_| ReferenceParseErrorCode::InvalidVerseNumberFormat)?;
-                        return Ok(ReferenceParseResult {
-                            book_name,
-                            chapter,
-                            reference_type: ReferenceParseResultType::VerseFromOnwards {
-                                number_from,
-                            },
-                        });
-                    } else {
-                        break 'collect_number;
-                    }
+                ReferenceParseResultType::VerseFromTo {
+                    number_from: number,
+                    number_to: end_number,
                 }
-
-                let number_str = &value[i..i + number_str_end];
-                let number = number_str
-                    .parse::<u8>()
-                    .map_err(|_| ReferenceParseErrorCode::InvalidChapterFormat)?;
-                return Ok(ReferenceParseResult {
-                    book_name,
-                    chapter,
-                    reference_type: ReferenceParseResultType::Verse { number },
-                });
             }
-            // If a book name is already found, then expect a chapter number to follow it.
-            else if book_name != EMPTY_STRING {
-                let mut chapter_str_end = 1;
-
-                'collect_chapter: while let Some((_, chapter_c)) = value_chars.peek() {
-                    if chapter_c.is_digit(10) {
-                        value_chars.next();
-                        chapter_str_end += 1;
-                    } else if *chapter_c == ':' {
-                        // If a chapter and verse number separator (:) is found, then store chapter
-                        // and start the next iteration to expect to collect a verse number.
-                        value_chars.next();
-                        let chapter_str = &value[i..i + chapter_str_end];
-
-                        chapter = chapter_str
-                            .parse::<u8>()
-                            .map_err(|_| ReferenceParseErrorCode::InvalidChapterFormat)?;
-
-                        if chapter == 0 {
-                            return Err(ReferenceParseErrorCode::InvalidChapterValue);
-                        }
-
-                        continue 'value_chars_loop;
-                    } else {
-                        break 'collect_chapter;
-                    }
-                }
-
-                let chapter_str = &value[i..i + chapter_str_end];
-                let chapter = chapter_str
-                    .parse::<u8>()
-                    .map_err(|_| ReferenceParseErrorCode::InvalidChapterFormat)?;
+        } else {
+            ReferenceParseResultType::Verse { number }
+        }
+    } else if scanner.skip_range_separator(mode) {
+        // A range between bare numbers (e.g. "John 3-5") could mean either chapters or verses.
+        if mode == ParseMode::Strict {
+            return Err(ReferenceParseErrorCode::AmbiguousRange);
+        }
+        scanner.skip_lenient_whitespace(mode);
+        let chapter_to = scanner.parse_number(ReferenceParseErrorCode::InvalidChapterFormat)?;
 
-                return Ok(ReferenceParseResult {
-                    book_name,
-                    chapter,
-                    reference_type: ReferenceParseResultType::Chapter,
-                });
-            }
+        // Ensure that the end chapter is greater than the start chapter.
+        if chapter_to < chapter {
+            return Err(ReferenceParseErrorCode::InvalidRangeBetweenChapters);
         }
-    }
+        if chapter_to == chapter {
+            ReferenceParseResultType::Chapter
+        } else {
+            ReferenceParseResultType::ChapterFromTo { chapter_to }
+        }
+    } else {
+        ReferenceParseResultType::Chapter
+    };
 
-    Err(ReferenceParseErrorCode::UnknownError)
+    scanner.finish(mode)?;
+
+    Ok(ReferenceParseResult {
+        book_name,
+        chapter,
+        reference_type,
+    })
+}
+/// Checks that a book name is capitalized conventionally, e.g. "John", "1 John" or "II Kings",
+/// but not "john" or "JOHN".
+fn is_book_name_capitalized(book_name: &str) -> bool {
+    let mut words = book_name
+        .split_whitespace()
+        .flat_map(|word| word.split(|c: char| c.is_ascii_digit()))
+        .filter(|word| !word.is_empty());
+    let Some(mut word) = words.next() else {
+        return false;
+    };
+    // Skip a book number in Roman numerals.
+    if ["I", "II", "III"].contains(&word) {
+        let Some(next_word) = words.next() else {
+            return false;
+        };
+        word = next_word;
+    }
+    let mut chars = word.chars().filter(|c| c.is_alphabetic());
+    chars.next().is_some_and(char::is_uppercase) && chars.all(char::is_lowercase)
+}
+/// Returns whether a book name has more than one word after its book number,
+/// e.g. "Song of Solomon" but not "1 John".
+fn has_many_words(book_name: &str) -> bool {
+    book_name
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start()
+        .contains(char::is_whitespace)
+}
+/// Parses a string of Bible references separated by semicolons (;)
+/// into parse result objects using the default configuration.
+pub fn parse_references(
+    value: &str,
+) -> Result<Vec<ReferenceParseResult<'_>>, ReferenceParseErrorCode> {
+    parse_references_with_config(value, &ParserConfig::default())
 }
-pub fn parse_references(value: &str) -> Result<Vec<ReferenceParseResult>, ReferenceParseErrorCode> {
+/// Parses a string of Bible references separated by semicolons (;)
+/// into parse result objects using the given configuration.
+pub fn parse_references_with_config<'a>(
+    value: &'a str,
+    config: &ParserConfig,
+) -> Result<Vec<ReferenceParseResult<'a>>, ReferenceParseErrorCode> {
     let mut references = Vec::new();
 
     for reference_str in value.split(';') {
-        let reference = parse_reference(reference_str)?;
+        // Lenient parsing allows a trailing semicolon and other empty references.
+        if config.mode == ParseMode::Lenient && reference_str.trim().is_empty() {
+            continue;
+        }
+        let reference = parse_reference_with_config(reference_str.trim_start(), config)?;
         references.push(reference);
     }
 
@@ -467,7 +702,7 @@ mod tests {
         let project_dir = env::current_dir().unwrap();
         let xml_file_path = project_dir.join("assets/kjv.xml");
         let file = File::open(xml_file_path).unwrap();
-        let source = OsisSource::from_file(file);
+        let source = OsisSource::from_file(file).unwrap();
         let parse_result = ReferenceParseResult {
             book_name: "John",
             chapter: 3,
@@ -483,16 +718,46 @@ mod tests {
             vec![Reference {
                 chapter: 3,
                 number: 1,
-                content: String::from("There was a man of the Pharisees, named Nicodemus, a ruler of the Jews:")
+                content: VerseContent::from("There was a man of the Pharisees, named Nicodemus, a ruler of the Jews:")
             },
             Reference {
                 chapter: 3,
                 number: 2,
-                content: String::from("The same came to Jesus by night, and said unto him, Rabbi, we know that thou art a teacher come from God: for no man can do these miracles that thou doest, except God be with him.")
+                content: VerseContent::from("The same came to Jesus by night, and said unto him, Rabbi, we know that thou art a teacher come from God: for no man can do these miracles that thou doest, except God be with him.")
             }]
         );
     }
 
+    #[test]
+    fn resolve_book_with_book_number() {
+        for reference in ["1 John 3", "1John 3", "I John 3", "1 Jn 3"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(parse_result.book(), Some(Book::FirstJohn), "{reference}");
+            assert_eq!(
+                parse_result.verse_id_range().map(|range| range.start()),
+                Some(VerseId::new(Book::FirstJohn, 3, 1)),
+                "{reference}"
+            );
+        }
+        assert_eq!(parse_reference("John 3").unwrap().book(), Some(Book::John));
+        // 1 John has only 5 chapters.
+        assert_eq!(parse_reference("1 John 15").unwrap().verse_id_range(), None);
+    }
+    #[test]
+    fn convert_reference_to_verse_id_range() {
+        let range = parse_reference("John 3:16+")
+            .unwrap()
+            .verse_id_range()
+            .unwrap();
+        assert_eq!(range.start(), VerseId::new(Book::John, 3, 16));
+        assert_eq!(range.end(), VerseId::new(Book::John, 3, 36));
+
+        let range = parse_reference("Ps 23").unwrap().verse_id_range().unwrap();
+        assert_eq!(range.iter().count(), 6);
+
+        // John has only 21 chapters.
+        assert_eq!(parse_reference("John 22").unwrap().verse_id_range(), None);
+    }
     #[test]
     fn fail_parse_reference_to_many_verses_with_invalid_range_between_verse_numbers() {
         // The end verse number is less than the start verse number,
@@ -504,6 +769,27 @@ mod tests {
         );
     }
     #[test]
+    fn fail_parse_reference_with_unknown_book_name() {
+        // Names in other languages parse unless known books are required.
+        assert_eq!(
+            parse_reference("Matteus 5:3").map(|parse_result| parse_result.book()),
+            Ok(None)
+        );
+        let config = ParserConfig {
+            require_known_book: true,
+            ..ParserConfig::default()
+        };
+        let error = parse_reference_with_config("Phillipians 4:13", &config).unwrap_err();
+        assert_eq!(
+            error,
+            ReferenceParseErrorCode::UnknownBookName {
+                suggestions: vec![Book::Philippians]
+            }
+        );
+        assert_eq!(error.suggestions(), [Book::Philippians]);
+        assert_eq!(error.to_string(Locale::En), "Unknown book name.");
+    }
+    #[test]
     fn parse_reference_to_chapter_with_one_word_book_name() {
         let parse_result = parse_reference("John 3").unwrap();
         assert_eq!(
@@ -521,7 +807,7 @@ mod tests {
         assert_eq!(
             parse_result,
             ReferenceParseResult {
-                book_name: "John",
+                book_name: "1 John",
                 chapter: 3,
                 reference_type: ReferenceParseResultType::Chapter
             }
@@ -531,7 +817,7 @@ mod tests {
         assert_eq!(
             parse_result,
             ReferenceParseResult {
-                book_name: "John",
+                book_name: "1 John",
                 chapter: 15,
                 reference_type: ReferenceParseResultType::Chapter
             }
@@ -587,6 +873,208 @@ mod tests {
         );
     }
     #[test]
+    fn parse_reference_to_many_verses_across_chapters() {
+        let parse_result = parse_reference("John 3:16-4:2").unwrap();
+        assert_eq!(
+            parse_result,
+            ReferenceParseResult {
+                book_name: "John",
+                chapter: 3,
+                reference_type: ReferenceParseResultType::VerseFromToAcrossChapters {
+                    number_from: 16,
+                    chapter_to: 4,
+                    number_to: 2
+                }
+            }
+        );
+
+        let parse_result = parse_reference("John 3:1-3:2").unwrap();
+        assert_eq!(
+            parse_result.reference_type,
+            ReferenceParseResultType::VerseFromTo {
+                number_from: 1,
+                number_to: 2
+            }
+        );
+
+        assert_eq!(
+            parse_reference("John 4:1-3:2"),
+            Err(ReferenceParseErrorCode::InvalidRangeBetweenChapters)
+        );
+    }
+    #[test]
+    fn parse_reference_to_whole_book() {
+        let parse_result = parse_reference("Song of Solomon").unwrap();
+        assert_eq!(
+            parse_result,
+            ReferenceParseResult {
+                book_name: "Song of Solomon",
+                chapter: 0,
+                reference_type: ReferenceParseResultType::Book
+            }
+        );
+    }
+    #[test]
+    fn expand_reference_into_verses() {
+        let verses = parse_reference("John 3:35-4:2")
+            .unwrap()
+            .verses()
+            .unwrap()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(verses, ["John.3.35", "John.3.36", "John.4.1", "John.4.2"]);
+
+        let mut verses = parse_reference("Obadiah").unwrap().verses().unwrap();
+        assert_eq!(verses.next(), Some(VerseId::new(Book::Obadiah, 1, 1)));
+        assert_eq!(verses.last(), Some(VerseId::new(Book::Obadiah, 1, 21)));
+    }
+    #[test]
+    fn count_verses_in_references() {
+        let count = |value| parse_reference(value).unwrap().verse_count();
+        assert_eq!(count("John 3:16"), Some(1));
+        assert_eq!(count("John 3:16+"), Some(21));
+        assert_eq!(count("John 3:1-2"), Some(2));
+        assert_eq!(count("Ps 119"), Some(176));
+        assert_eq!(count("John 3:16-5:2"), Some(21 + 54 + 2));
+        assert_eq!(count("Genesis"), Some(1533));
+        assert_eq!(count("John 3:37"), None);
+
+        for value in ["Gen 49:3-50:2", "Matthew", "Ps 118:20-120:3"] {
+            let parse_result = parse_reference(value).unwrap();
+            assert_eq!(
+                parse_result.verse_count(),
+                parse_result.verses().map(|verses| verses.count())
+            );
+        }
+    }
+    #[test]
+    fn parse_reference_in_strict_mode() {
+        let config = ParserConfig::strict();
+        let parse = |value| parse_reference_with_config(value, &config);
+
+        assert_eq!(
+            parse("1 John 3:16").unwrap(),
+            ReferenceParseResult {
+                book_name: "1 John",
+                chapter: 3,
+                reference_type: ReferenceParseResultType::Verse { number: 16 }
+            }
+        );
+        assert_eq!(
+            parse("II Kings 2:11").unwrap().book(),
+            Some(Book::SecondKings)
+        );
+
+        assert_eq!(
+            parse("John 3:16 foo"),
+            Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+        );
+        assert_eq!(
+            parse("John 3:16."),
+            Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+        );
+        assert_eq!(
+            parse("John 3:"),
+            Err(ReferenceParseErrorCode::InvalidVerseNumberFormat)
+        );
+        assert_eq!(
+            parse("John 3-5"),
+            Err(ReferenceParseErrorCode::AmbiguousRange)
+        );
+        assert_eq!(
+            parse("JOHN 3:16"),
+            Err(ReferenceParseErrorCode::InvalidBookNameFormat)
+        );
+        assert_eq!(
+            parse("John  3:16"),
+            Err(ReferenceParseErrorCode::InvalidWhitespace)
+        );
+        assert_eq!(
+            parse("1Jn3:16"),
+            Err(ReferenceParseErrorCode::InvalidWhitespace)
+        );
+        assert_eq!(
+            parse("1 \t  John 3:16"),
+            Err(ReferenceParseErrorCode::InvalidWhitespace)
+        );
+        assert_eq!(
+            parse(" John 3:16"),
+            Err(ReferenceParseErrorCode::InvalidWhitespace)
+        );
+        assert_eq!(
+            parse("John foo"),
+            Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+        );
+        assert_eq!(
+            parse("Jn x 3:16"),
+            Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+        );
+        assert_eq!(
+            parse("Song of Solomon 2:1").unwrap().book(),
+            Some(Book::SongOfSolomon)
+        );
+        // Unknown names of one word, e.g. in other languages, are still accepted.
+        assert_eq!(parse("Matteus 5:3").unwrap().book_name, "Matteus");
+    }
+    #[test]
+    fn parse_reference_in_lenient_mode() {
+        let config = ParserConfig::lenient();
+        let parse = |value| parse_reference_with_config(value, &config);
+
+        assert_eq!(
+            parse("1Jn3:16").unwrap(),
+            ReferenceParseResult {
+                book_name: "1Jn",
+                chapter: 3,
+                reference_type: ReferenceParseResultType::Verse { number: 16 }
+            }
+        );
+        assert_eq!(
+            parse("  JOHN  3 : 16 - 18 . ").unwrap(),
+            ReferenceParseResult {
+                book_name: "JOHN",
+                chapter: 3,
+                reference_type: ReferenceParseResultType::VerseFromTo {
+                    number_from: 16,
+                    number_to: 18
+                }
+            }
+        );
+        assert_eq!(
+            parse("John 3.16–4.2").unwrap().reference_type,
+            ReferenceParseResultType::VerseFromToAcrossChapters {
+                number_from: 16,
+                chapter_to: 4,
+                number_to: 2
+            }
+        );
+        assert_eq!(
+            parse("John 3-5").unwrap().reference_type,
+            ReferenceParseResultType::ChapterFromTo { chapter_to: 5 }
+        );
+        assert_eq!(parse("Jude.").unwrap().book_name, "Jude");
+        assert_eq!(
+            parse("John 3:").unwrap().reference_type,
+            ReferenceParseResultType::Chapter
+        );
+        assert_eq!(
+            parse("John 3:16-18:").unwrap().reference_type,
+            ReferenceParseResultType::VerseFromTo {
+                number_from: 16,
+                number_to: 18
+            }
+        );
+
+        assert_eq!(
+            parse("John 3:16 foo"),
+            Err(ReferenceParseErrorCode::UnexpectedTrailingCharacters)
+        );
+        assert_eq!(
+            parse_references_with_config("John 3:16; Ps 23;", &config).map(|r| r.len()),
+            Ok(2)
+        );
+    }
+    #[test]
     fn parse_references() {
         let parse_result = super::parse_references("John 3:1-2; John 3:4-5").unwrap();
         assert_eq!(
@@ -0,0 +1,398 @@
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use xml::reader::XmlEvent;
+
+use crate::{
+    osis, sword, CompiledSource, DelimitedConfig, DelimitedSource, JsonSource, OsisSource, Source,
+    SourceError, SourceMetadata, SwordSource, UsfmSource, UsxSource, ZefaniaSource,
+};
+
+/// Format of a source found in a library.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SourceFormat {
+    Osis,
+    Zefania,
+    /// Directory of USX book files.
+    Usx,
+    /// Directory of USFM book files.
+    Usfm,
+    Json,
+    Delimited(DelimitedConfig),
+    Sword,
+}
+
+/// Source found in a library, which is not opened until it's needed.
+#[derive(Clone, Debug)]
+pub struct LibraryEntry {
+    pub format: SourceFormat,
+    /// File or directory of the source, or the configuration file of a SWORD module.
+    pub path: PathBuf,
+    /// Metadata known without opening the source. The abbreviation is always known,
+    /// as it defaults to the file name without the extension.
+    pub metadata: SourceMetadata,
+}
+impl LibraryEntry {
+    /// Returns the abbreviation that identifies the source, e.g. "KJV".
+    pub fn abbreviation(&self) -> &str {
+        self.metadata.abbreviation.as_deref().unwrap_or_default()
+    }
+    /// Opens the source compiled with [`CompiledSource`]. The source is compiled next to
+    /// its file on first use and whenever the file changes.
+    ///
+    /// Directories of USX and USFM books are compiled again when any book file changes,
+    /// and SWORD modules when their configuration file or any of their data files changes.
+    pub fn open(&self) -> Result<CompiledSource, SourceError> {
+        let compiled_path = CompiledSource::default_path(&self.path);
+        let source_paths = self.source_files()?;
+        CompiledSource::open_or_compile_files(&source_paths, &compiled_path, || {
+            let path = self.path.as_path();
+            let open_file = || File::open(path).map_err(|error| SourceError::io(path, error));
+            let index = match self.format {
+                SourceFormat::Osis => {
+                    let data = fs::read(path).map_err(|error| SourceError::io(path, error))?;
+                    OsisSource::from_data(data).index()?
+                }
+                SourceFormat::Zefania => ZefaniaSource::from_file(open_file()?)?.into_index(),
+                SourceFormat::Usx => UsxSource::from_directory(path)?.into_index(),
+                SourceFormat::Usfm => UsfmSource::from_directory(path)?.into_index(),
+                SourceFormat::Json => JsonSource::from_file(open_file()?)?.into_index(),
+                SourceFormat::Delimited(config) => {
+                    DelimitedSource::from_file(open_file()?, &config)?.into_index()
+                }
+                SourceFormat::Sword => SwordSource::from_conf_file(path)?.into_index(),
+            };
+            // Formats without metadata are still known by their abbreviations.
+            Ok(match index.metadata().is_empty() {
+                true => index.with_metadata(self.metadata.clone()),
+                false => index,
+            })
+        })
+    }
+
+    /// Returns the files the source is read from, whose changes call for compiling it again.
+    fn source_files(&self) -> Result<Vec<PathBuf>, SourceError> {
+        let book_extensions: &[&str] = match self.format {
+            SourceFormat::Usx => &["usx"],
+            SourceFormat::Usfm => &["usfm", "sfm"],
+            SourceFormat::Sword => return sword::module_files(&self.path),
+            _ => return Ok(vec![self.path.clone()]),
+        };
+        // The directory itself changes when books are added or removed.
+        let mut paths = vec![self.path.clone()];
+        paths.extend(
+            sorted_paths(&self.path)?
+                .into_iter()
+                .filter(|path| has_extension(path, book_extensions)),
+        );
+        Ok(paths)
+    }
+    /// Returns whether the given name identifies the source by its abbreviation
+    /// or its file name, ignoring letter case and anything but letters and digits,
+    /// e.g. "R1933/-38" for a file named `r1933-38.xml`.
+    fn is_named(&self, name: &str) -> bool {
+        let name = normalize_name(name);
+        let file_name = self
+            .path
+            .file_stem()
+            .map(|file_name| file_name.to_string_lossy())
+            .unwrap_or_default();
+        !name.is_empty()
+            && (normalize_name(self.abbreviation()) == name || normalize_name(&file_name) == name)
+    }
+}
+
+/// Sources found in directories, which are opened by their abbreviations.
+///
+/// Sources are recognized by their file extensions and, for XML, their root elements:
+/// - OSIS (`.xml` or `.osis` with `<osis>`) and Zefania XML (`.xml` with `<XMLBIBLE>`) files.
+/// - JSON (`.json`) files.
+/// - Tab-separated (`.tsv`) and comma-separated (`.csv`) files of book, chapter, verse and text.
+/// - Directories of USX (`.usx`) or USFM (`.usfm` or `.sfm`) book files.
+/// - SWORD modules, if a directory is a SWORD directory, i.e. it contains `mods.d`.
+///
+/// Other files are ignored.
+#[derive(Debug, Default)]
+pub struct Library {
+    entries: Vec<LibraryEntry>,
+    /// Errors of files and directories that couldn't be scanned.
+    skipped: Vec<SourceError>,
+}
+impl Library {
+    /// Scans the given directories for sources. Directories that don't exist are skipped,
+    /// so that default locations may be given whether or not anything is installed there.
+    ///
+    /// Files and directories that can't be read, e.g. a SWORD module configuration without
+    /// a module name, are skipped as well, and their errors are kept in [`Library::skipped`].
+    pub fn scan<P: AsRef<Path>>(directories: impl IntoIterator<Item = P>) -> Self {
+        let mut library = Self::default();
+        let mut scanned_directories = vec![];
+        for directory in directories {
+            let Ok(directory) = directory.as_ref().canonicalize() else {
+                continue;
+            };
+            if !directory.is_dir() || scanned_directories.contains(&directory) {
+                continue;
+            }
+            library.scan_directory(&directory);
+            scanned_directories.push(directory);
+        }
+        library
+    }
+
+    /// Returns the sources found, in the order of their directories and file names.
+    pub fn entries(&self) -> &[LibraryEntry] {
+        &self.entries
+    }
+    /// Finds a source by its abbreviation or file name. If many sources have the name,
+    /// the one found first is returned.
+    pub fn find(&self, name: &str) -> Option<&LibraryEntry> {
+        self.entries.iter().find(|entry| entry.is_named(name))
+    }
+    /// Returns the errors of the files and directories skipped when scanning.
+    pub fn skipped(&self) -> &[SourceError] {
+        &self.skipped
+    }
+    /// Opens a source by its abbreviation or file name.
+    pub fn open(&self, name: &str) -> Result<CompiledSource, SourceError> {
+        let entry = self
+            .find(name)
+            .ok_or_else(|| SourceError::UnknownSource(name.to_string()))?;
+        entry.open()
+    }
+
+    fn scan_directory(&mut self, directory: &Path) {
+        let conf_directory = directory.join("mods.d");
+        if conf_directory.is_dir() {
+            for conf_path in self.sorted_paths(&conf_directory) {
+                if has_extension(&conf_path, &["conf"]) {
+                    match sword::read_module_metadata(&conf_path) {
+                        Ok(metadata) => self.push(SourceFormat::Sword, conf_path, metadata),
+                        Err(err) => self.skipped.push(err),
+                    }
+                }
+            }
+        }
+
+        for path in self.sorted_paths(directory) {
+            if path.is_dir() {
+                let book_paths = self.sorted_paths(&path);
+                if book_paths.iter().any(|path| has_extension(path, &["usx"])) {
+                    self.push(SourceFormat::Usx, path, SourceMetadata::default());
+                } else if book_paths
+                    .iter()
+                    .any(|path| has_extension(path, &["usfm", "sfm"]))
+                {
+                    self.push(SourceFormat::Usfm, path, SourceMetadata::default());
+                }
+                continue;
+            }
+            if has_extension(&path, &["xml", "osis"]) {
+                match root_element(&path).as_deref() {
+                    Some("osis") => {
+                        let metadata = File::open(&path)
+                            .ok()
+                            .and_then(|file| osis::read_metadata(BufReader::new(file)).ok())
+                            .unwrap_or_default();
+                        self.push(SourceFormat::Osis, path, metadata);
+                    }
+                    Some(name) if name.eq_ignore_ascii_case("XMLBIBLE") => {
+                        self.push(SourceFormat::Zefania, path, SourceMetadata::default());
+                    }
+                    _ => {}
+                }
+            } else if has_extension(&path, &["json"]) {
+                self.push(SourceFormat::Json, path, SourceMetadata::default());
+            } else if has_extension(&path, &["tsv"]) {
+                let format = SourceFormat::Delimited(DelimitedConfig::tsv());
+                self.push(format, path, SourceMetadata::default());
+            } else if has_extension(&path, &["csv"]) {
+                let format = SourceFormat::Delimited(DelimitedConfig::columns(','));
+                self.push(format, path, SourceMetadata::default());
+            }
+        }
+    }
+    /// Returns the paths of the entries of a directory, or none if it can't be read.
+    fn sorted_paths(&mut self, directory: &Path) -> Vec<PathBuf> {
+        sorted_paths(directory).unwrap_or_else(|err| {
+            self.skipped.push(err);
+            vec![]
+        })
+    }
+    fn push(&mut self, format: SourceFormat, path: PathBuf, mut metadata: SourceMetadata) {
+        if metadata.abbreviation.is_none() {
+            metadata.abbreviation = path
+                .file_stem()
+                .map(|file_name| file_name.to_string_lossy().into_owned());
+        }
+        self.entries.push(LibraryEntry {
+            format,
+            path,
+            metadata,
+        });
+    }
+}
+
+/// Returns the paths of the entries of a directory, sorted for a deterministic order.
+fn sorted_paths(directory: &Path) -> Result<Vec<PathBuf>, SourceError> {
+    let entries = fs::read_dir(directory).map_err(|error| SourceError::io(directory, error))?;
+    let mut paths = entries
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|error| SourceError::io(directory, error))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+    Ok(paths)
+}
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|expected| extension.eq_ignore_ascii_case(expected))
+        })
+}
+/// Returns the name of the root element of an XML file, or `None` if it's not XML.
+fn root_element(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let mut parser = xml::EventReader::new(BufReader::new(file));
+    loop {
+        match parser.next().ok()? {
+            XmlEvent::StartElement { name, .. } => return Some(name.local_name),
+            XmlEvent::EndDocument => return None,
+            _ => {}
+        }
+    }
+}
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_reference;
+
+    fn library_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "raqiya-bible-reference-library-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+    fn plain_texts(source: &CompiledSource, reference: &str) -> Vec<String> {
+        source
+            .find_content(&parse_reference(reference).unwrap())
+            .unwrap()
+            .iter()
+            .map(|reference| reference.content.plain_text())
+            .collect()
+    }
+
+    #[test]
+    fn scan_directories_for_sources() {
+        let directory = library_directory("scan");
+        fs::write(
+            directory.join("kjv.xml"),
+            r#"<osis><osisText osisIDWork="KJV">
+            <header><work osisWork="KJV"><title>King James Version</title></work></header>
+            </osisText></osis>"#,
+        )
+        .unwrap();
+        fs::write(directory.join("r1933-38.xml"), "<XMLBIBLE></XMLBIBLE>").unwrap();
+        fs::write(directory.join("web.json"), "[]").unwrap();
+        fs::write(directory.join("notes.xml"), "<notes></notes>").unwrap();
+        fs::write(directory.join("readme.txt"), "").unwrap();
+        fs::create_dir_all(directory.join("bsb")).unwrap();
+        fs::write(directory.join("bsb/43JHN.usfm"), "").unwrap();
+
+        fs::create_dir_all(directory.join("mods.d")).unwrap();
+        fs::write(directory.join("mods.d/broken.conf"), "DataPath=./modules/").unwrap();
+
+        let library = Library::scan([
+            directory.clone(),
+            directory.join("missing"),
+            directory.clone(),
+        ]);
+        let entries = library
+            .entries()
+            .iter()
+            .map(|entry| (entry.abbreviation(), entry.format))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                ("bsb", SourceFormat::Usfm),
+                ("KJV", SourceFormat::Osis),
+                ("r1933-38", SourceFormat::Zefania),
+                ("web", SourceFormat::Json)
+            ]
+        );
+        assert_eq!(
+            library.find("kjv").unwrap().metadata.title.as_deref(),
+            Some("King James Version")
+        );
+        assert_eq!(
+            library.find("R1933/-38").unwrap().format,
+            SourceFormat::Zefania
+        );
+        assert!(library.find("notes").is_none());
+        assert!(matches!(
+            library.skipped(),
+            [SourceError::UnexpectedStructure(_)]
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+    #[test]
+    fn open_sources_by_name() {
+        let directory = library_directory("open");
+        fs::write(
+            directory.join("kjv.tsv"),
+            "book\tchapter\tverse\ttext\nJohn\t11\t35\tJesus wept.\n",
+        )
+        .unwrap();
+        let library = Library::scan([&directory]);
+
+        let source = library.open("KJV").unwrap();
+        assert_eq!(plain_texts(&source, "John 11:35"), ["Jesus wept."]);
+        assert_eq!(source.metadata().abbreviation.as_deref(), Some("kjv"));
+        assert!(CompiledSource::default_path(&directory.join("kjv.tsv")).exists());
+        assert!(matches!(
+            library.open("ASV"),
+            Err(SourceError::UnknownSource(name)) if name == "ASV"
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+    #[test]
+    fn compile_directory_again_when_book_changes() {
+        let directory = library_directory("rebuild");
+        let book_path = directory.join("web/43JHN.usfm");
+        fs::create_dir_all(book_path.parent().unwrap()).unwrap();
+        fs::write(&book_path, "\\id JHN\n\\c 11\n\\p\n\\v 35 Jesus wept.").unwrap();
+        let library = Library::scan([&directory]);
+        let source = library.open("web").unwrap();
+        assert_eq!(plain_texts(&source, "John 11:35"), ["Jesus wept."]);
+
+        fs::write(
+            &book_path,
+            "\\id JHN\n\\c 11\n\\p\n\\v 35 Jesus shed tears.",
+        )
+        .unwrap();
+        let source = library.open("web").unwrap();
+        assert_eq!(plain_texts(&source, "John 11:35"), ["Jesus shed tears."]);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
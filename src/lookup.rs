@@ -0,0 +1,250 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    Book, Locale, Reference, ReferenceParseResult, ReferenceParseResultType, SourceError, VerseId,
+    VerseIdRange,
+};
+
+/// Content found for a reference, along with the referenced books, chapters and verses
+/// that the source doesn't have.
+///
+/// Missing verses are determined by the KJV versification. Verses that the versification
+/// doesn't have, e.g. "John 3:99", are missing unless the source has them.
+#[derive(Debug, PartialEq)]
+pub struct Lookup {
+    pub references: Vec<Reference>,
+    pub missing: Vec<Missing>,
+}
+impl Lookup {
+    /// Finds out which of the verses referenced by `parse_result` are missing from
+    /// the references found.
+    pub(crate) fn new(parse_result: &ReferenceParseResult, references: Vec<Reference>) -> Self {
+        let Some(book) = parse_result.book() else {
+            return Self {
+                references,
+                missing: vec![Missing::UnknownBook(parse_result.book_name.to_string())],
+            };
+        };
+        let found = references
+            .iter()
+            .map(|reference| (reference.chapter, reference.number))
+            .collect::<HashSet<_>>();
+        let mut missing = vec![];
+        for (chapter, number_from, number_to) in requested_chapters(parse_result, book) {
+            let has_chapter = found
+                .iter()
+                .any(|(found_chapter, _)| *found_chapter == chapter);
+            if !has_chapter && number_from == 1 && number_to.is_none() {
+                missing.push(Missing::Chapter { book, chapter });
+                continue;
+            }
+            // An open-ended range ends where the chapter ends in the versification, or
+            // where it ends in the source if the versification doesn't have the chapter.
+            let number_to = number_to
+                .or_else(|| book.verse_count(chapter))
+                .or_else(|| {
+                    found
+                        .iter()
+                        .filter(|(found_chapter, _)| *found_chapter == chapter)
+                        .map(|(_, number)| *number)
+                        .max()
+                })
+                .unwrap_or(number_from);
+            let mut missing_from = None;
+            for number in number_from..=number_to {
+                let is_missing = !found.contains(&(chapter, number));
+                match (missing_from, is_missing) {
+                    (None, true) => missing_from = Some(number),
+                    (Some(from), false) => {
+                        missing.push(Missing::verses(book, chapter, from, number - 1));
+                        missing_from = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(from) = missing_from {
+                missing.push(Missing::verses(book, chapter, from, number_to));
+            }
+        }
+
+        let is_whole_book_missing = parse_result.reference_type == ReferenceParseResultType::Book
+            && missing
+                .iter()
+                .all(|missing| matches!(missing, Missing::Chapter { .. }))
+            && references.is_empty();
+        if is_whole_book_missing {
+            missing = vec![Missing::Book(book)];
+        }
+        Self {
+            references,
+            missing,
+        }
+    }
+
+    /// Returns whether the source has every referenced verse.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+    /// Returns the references found if the source has every referenced verse,
+    /// or else fails with [`SourceError::ReferenceNotFound`].
+    pub fn into_complete(self) -> Result<Vec<Reference>, SourceError> {
+        if !self.is_complete() {
+            return Err(SourceError::ReferenceNotFound {
+                missing: self.missing,
+            });
+        }
+        Ok(self.references)
+    }
+}
+
+/// Part of a reference that a source doesn't have.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Missing {
+    /// Book name that matches no book, so it can't be in any source.
+    UnknownBook(String),
+    Book(Book),
+    Chapter {
+        book: Book,
+        chapter: u8,
+    },
+    /// Verses within a chapter, e.g. the last verses of an onwards range that
+    /// the source cuts short.
+    Verses(VerseIdRange),
+}
+impl Missing {
+    fn verses(book: Book, chapter: u8, number_from: u8, number_to: u8) -> Self {
+        let start = VerseId::new(book, chapter, number_from);
+        Missing::Verses(
+            VerseIdRange::new(start, VerseId::new(book, chapter, number_to))
+                .unwrap_or(VerseIdRange::single(start)),
+        )
+    }
+}
+impl fmt::Display for Missing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Missing::UnknownBook(name) => write!(f, "{name}"),
+            Missing::Book(book) => write!(f, "{}", book.name(Locale::En)),
+            Missing::Chapter { book, chapter } => {
+                write!(f, "{} {chapter}", book.name(Locale::En))
+            }
+            Missing::Verses(range) => {
+                let (start, end) = (range.start(), range.end());
+                write!(
+                    f,
+                    "{} {}:{}",
+                    start.book().name(Locale::En),
+                    start.chapter(),
+                    start.verse()
+                )?;
+                if end != start {
+                    write!(f, "-{}", end.verse())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the chapters of a reference, each with the first verse and the last verse,
+/// if the reference limits it, e.g. (3, 16, None) for "John 3:16+".
+fn requested_chapters(
+    parse_result: &ReferenceParseResult,
+    book: Book,
+) -> Vec<(u8, u8, Option<u8>)> {
+    let chapter = parse_result.chapter;
+    match parse_result.reference_type {
+        ReferenceParseResultType::Book => (1..=book.chapter_count())
+            .map(|chapter| (chapter, 1, None))
+            .collect(),
+        ReferenceParseResultType::Chapter => vec![(chapter, 1, None)],
+        ReferenceParseResultType::ChapterFromTo { chapter_to } => (chapter..=chapter_to)
+            .map(|chapter| (chapter, 1, None))
+            .collect(),
+        ReferenceParseResultType::Verse { number } => vec![(chapter, number, Some(number))],
+        ReferenceParseResultType::VerseFromOnwards { number_from } => {
+            vec![(chapter, number_from, None)]
+        }
+        ReferenceParseResultType::VerseFromTo {
+            number_from,
+            number_to,
+        } => vec![(chapter, number_from, Some(number_to))],
+        ReferenceParseResultType::VerseFromToAcrossChapters {
+            number_from,
+            chapter_to,
+            number_to,
+        } => {
+            let mut chapters = vec![(chapter, number_from, None)];
+            chapters.extend((chapter + 1..chapter_to).map(|chapter| (chapter, 1, None)));
+            chapters.push((chapter_to, 1, Some(number_to)));
+            chapters
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_reference, IndexedSource, Source, VerseContent, VerseIndex};
+
+    fn source() -> IndexedSource {
+        let verses = [(3, 34), (3, 35), (4, 1), (4, 3)]
+            .into_iter()
+            .map(|(chapter, verse)| {
+                (
+                    VerseId::new(Book::John, chapter, verse),
+                    VerseContent::from("..."),
+                )
+            })
+            .collect();
+        IndexedSource::new(VerseIndex::new(verses), vec![], vec![])
+    }
+    fn missing(reference: &str) -> Vec<String> {
+        source()
+            .look_up(&parse_reference(reference).unwrap())
+            .unwrap()
+            .missing
+            .iter()
+            .map(Missing::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn report_missing_books_chapters_and_verses() {
+        assert!(missing("John 3:34-35").is_empty());
+        assert_eq!(missing("John 3:99"), ["John 3:99"]);
+        assert_eq!(missing("John 3:34-37"), ["John 3:36-37"]);
+        assert_eq!(missing("John 3:34+"), ["John 3:36"]);
+        assert_eq!(missing("John 3:35-4:3"), ["John 3:36", "John 4:2"]);
+        assert_eq!(missing("John 22"), ["John 22"]);
+        assert_eq!(missing("John 4-5"), ["John 4:2", "John 4:4-54", "John 5"]);
+        assert_eq!(missing("Acts"), ["Acts"]);
+
+        let parse_result = ReferenceParseResult {
+            book_name: "Jhon",
+            chapter: 3,
+            reference_type: ReferenceParseResultType::Chapter,
+        };
+        assert_eq!(
+            source().look_up(&parse_result).unwrap().missing,
+            [Missing::UnknownBook("Jhon".to_string())]
+        );
+    }
+    #[test]
+    fn fail_look_up_of_incomplete_reference() {
+        let lookup = source()
+            .look_up(&parse_reference("John 3:34-36").unwrap())
+            .unwrap();
+        assert_eq!(lookup.references.len(), 2);
+        assert!(!lookup.is_complete());
+        assert_eq!(
+            lookup.into_complete().unwrap_err().to_string(),
+            "Not found: John 3:36"
+        );
+
+        let lookup = source()
+            .look_up(&parse_reference("John 3:34-35").unwrap())
+            .unwrap();
+        assert_eq!(lookup.into_complete().unwrap().len(), 2);
+    }
+}
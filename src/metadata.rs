@@ -0,0 +1,39 @@
+/// Descriptive information of a source, e.g. its title, language and rights.
+/// Each field is `None` if the source doesn't give it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceMetadata {
+    /// Full name of the text, e.g. "King James Version (1769)".
+    pub title: Option<String>,
+    /// Short name of the text, e.g. "KJV".
+    pub abbreviation: Option<String>,
+    /// Language of the text as a language code, e.g. "en" or "fi".
+    pub language: Option<String>,
+    pub publisher: Option<String>,
+    /// Copyright or license of the text, e.g. "Public Domain".
+    pub rights: Option<String>,
+    /// Versification the text follows, e.g. "KJV".
+    pub versification: Option<String>,
+    /// Identifier of the text in its format, e.g. "Bible.KJV" in OSIS.
+    pub identifier: Option<String>,
+}
+impl SourceMetadata {
+    /// Returns whether the source gives no metadata at all.
+    pub fn is_empty(&self) -> bool {
+        *self == EMPTY_METADATA
+    }
+    /// Returns the name to show for the source, which is its title or else its abbreviation.
+    pub fn name(&self) -> Option<&str> {
+        self.title.as_deref().or(self.abbreviation.as_deref())
+    }
+}
+
+/// Metadata of sources that give none.
+pub(crate) static EMPTY_METADATA: SourceMetadata = SourceMetadata {
+    title: None,
+    abbreviation: None,
+    language: None,
+    publisher: None,
+    rights: None,
+    versification: None,
+    identifier: None,
+};
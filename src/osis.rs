@@ -0,0 +1,1157 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+    ops::ControlFlow,
+};
+
+use xml::{attribute::OwnedAttribute, reader::XmlEvent};
+
+use crate::{
+    references_of_verses, Book, Break, Heading, HeadingType, IndexedSource, MorphologyCode, Note,
+    NoteType, Reference, ReferenceParseResult, Segment, Source, SourceError, SourceMetadata,
+    StrongsNumber, TextStyle, VerseContent, VerseId, VerseIdRange, VerseIndex, Word,
+};
+
+/// Represents a source of Bible content according to Open Scripture Information Standard (OSIS).
+/// It is stored in an XML format.
+///
+/// Verses may be marked either as container elements (`<verse osisID="John.3.16">...</verse>`)
+/// or as empty milestone elements (`<verse osisID="John.3.16" sID="John.3.16"/>` ...
+/// `<verse eID="John.3.16"/>`), in which case the verse text is collected between the start and
+/// end milestones, even when the verse crosses paragraph (`<p>`), line (`<l>`) or quotation (`<q>`)
+/// boundaries.
+///
+/// Verses combined into one element, e.g. `<verse osisID="John.4.1 John.4.2">`, have the same
+/// content under each of their verse IDs.
+///
+/// Words tagged with `<w lemma="strong:G26" morph="robinson:N-ASF">` are read as [`Word`] segments
+/// with their Strong's numbers and morphology codes.
+///
+/// Paragraphs (`<p>`), stanzas (`<lg>`) and poetic lines (`<l level="2">`) are read as
+/// [`Break`] segments, so that passages can be laid out the way the source intends.
+///
+/// Headings (`<title>`) are not part of verse content. They are read separately, together with
+/// book introductions (`<div type="introduction">`).
+///
+/// Metadata is read from the `<work>` element of the header that describes the text itself,
+/// i.e. the one whose `osisWork` matches the `osisIDWork` of `<osisText>`, or else the first one.
+///
+/// See https://en.wikipedia.org/wiki/Open_Scripture_Information_Standard for more information.
+pub struct OsisSource {
+    data: Vec<u8>,
+    metadata: SourceMetadata,
+}
+impl Source for OsisSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        let Some(bounds) = parse_result.verse_id_bounds() else {
+            return Ok(vec![]);
+        };
+        references_of_verses(parse_result, self.read_passage(bounds)?.verses)
+    }
+    fn metadata(&self) -> &SourceMetadata {
+        &self.metadata
+    }
+}
+impl OsisSource {
+    pub fn from_file(mut file: File) -> Result<Self, SourceError> {
+        let mut data = vec![];
+        file.read_to_end(&mut data)
+            .map_err(|error| SourceError::Io { path: None, error })?;
+        Ok(Self::from_data(data))
+    }
+    /// Creates a source from OSIS XML content already in memory.
+    ///
+    /// Only the header is parsed when created. If it isn't well-formed, the metadata is left
+    /// empty and the error is reported by lookups instead.
+    pub fn from_data(data: Vec<u8>) -> Self {
+        let metadata = read_metadata(Cursor::new(&data)).unwrap_or_default();
+        Self { data, metadata }
+    }
+
+    /// Finds every verse with a word tagged with the given Strong's number, in canonical order.
+    pub fn find_verses_with_strongs(
+        &self,
+        strongs: StrongsNumber,
+    ) -> Result<Vec<(VerseId, VerseContent)>, SourceError> {
+        let verse_reader = VerseReader::new(VerseIdRange::all()).with_strongs(strongs);
+        Ok(self.read(verse_reader)?.verses)
+    }
+    /// Finds headings that stand before or within the referenced verses, in canonical order.
+    pub fn find_headings(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Heading>, SourceError> {
+        let Some(bounds) = parse_result.verse_id_bounds() else {
+            return Ok(vec![]);
+        };
+        Ok(self.read_passage(bounds)?.headings)
+    }
+    /// Finds the title of a Psalm that is part of the original text, e.g. "A Psalm of David."
+    pub fn find_psalm_title(&self, chapter: u8) -> Result<Option<String>, SourceError> {
+        let first_verse = VerseIdRange::single(VerseId::new(Book::Psalms, chapter, 1));
+        Ok(self
+            .read_passage(first_verse)?
+            .headings
+            .into_iter()
+            .find(Heading::is_psalm_title)
+            .map(|heading| heading.text))
+    }
+    /// Finds the introduction of a book, which precedes its first chapter.
+    pub fn find_introduction(&self, book: Book) -> Result<Option<String>, SourceError> {
+        let first_verse = VerseIdRange::single(VerseId::new(book, 1, 1));
+        Ok(self
+            .read_passage(first_verse)?
+            .introductions
+            .into_iter()
+            .find(|(introduction_book, _)| *introduction_book == book)
+            .map(|(_, text)| text))
+    }
+
+    /// Parses the whole source once into an in-memory source indexed for fast lookups.
+    pub fn index(&self) -> Result<IndexedSource, SourceError> {
+        let passage = self.read_passage(VerseIdRange::all())?;
+        Ok(IndexedSource::new(
+            VerseIndex::new(passage.verses),
+            passage.headings,
+            passage.introductions,
+        )
+        .with_metadata(self.metadata.clone()))
+    }
+
+    /// Reads the verses whose verse IDs are within the given bounds,
+    /// along with their headings and the introductions of their books.
+    fn read_passage(&self, bounds: VerseIdRange) -> Result<Passage, SourceError> {
+        self.read(VerseReader::new(bounds))
+    }
+    fn read(&self, mut verse_reader: VerseReader) -> Result<Passage, SourceError> {
+        let file_reader = Cursor::new(&self.data);
+        let mut parser = xml::EventReader::new(file_reader);
+
+        loop {
+            let element = parser.next()?;
+            if let XmlEvent::EndDocument = element {
+                break;
+            }
+            if verse_reader.handle_event(element).is_break() {
+                break;
+            }
+        }
+
+        Ok(verse_reader.finish())
+    }
+}
+
+/// Verses read from a source, along with their headings and the introductions of their books.
+struct Passage {
+    verses: Vec<(VerseId, VerseContent)>,
+    headings: Vec<Heading>,
+    introductions: Vec<(Book, String)>,
+}
+
+/// Heading that has been read before the verse it stands before is known.
+pub(crate) struct PendingHeading {
+    pub(crate) heading_type: HeadingType,
+    pub(crate) canonical: bool,
+    pub(crate) text: String,
+}
+impl PendingHeading {
+    pub(crate) fn into_heading(self, verse_id: VerseId) -> Heading {
+        Heading {
+            heading_type: self.heading_type,
+            canonical: self.canonical,
+            text: self.text,
+            verse_id,
+        }
+    }
+}
+
+/// Reads content of verses within bounds from OSIS XML events.
+///
+/// Reading operates in line with the following assumptions:
+/// - Verses are in canonical order.
+/// - Verses don't overlap, i.e. a verse ends before the next one starts.
+struct VerseReader {
+    bounds: VerseIdRange,
+    /// Strong's number that verses must have a word tagged with to be kept, if any.
+    strongs: Option<StrongsNumber>,
+    verses: Vec<(VerseId, VerseContent)>,
+    /// Verse currently being read and its content so far.
+    current_verse: Option<(VerseId, VerseContent)>,
+    /// Further verse IDs within bounds of the verse currently being read, if it combines verses.
+    combined_verse_ids: Vec<VerseId>,
+    /// Whether a verse has started and not yet ended, even if it's outside bounds.
+    is_within_verse: bool,
+    /// Whether the latest verse element is a milestone. Milestones are empty elements,
+    /// so their end elements follow immediately and don't end the verse.
+    is_verse_milestone: bool,
+    /// Text style of each open element, the innermost last.
+    style_stack: Vec<TextStyle>,
+    /// Open quotation milestones (`<q sID="..."/>` without `<q eID="..."/>` yet),
+    /// each with its start ID and whether it contains words of Christ.
+    open_quotation_milestones: Vec<(String, bool)>,
+    /// Note currently being read and the depth of open elements within it.
+    current_note: Option<(Note, usize)>,
+    /// Tagged word currently being read.
+    current_word: Option<Word>,
+    headings: Vec<Heading>,
+    /// Breaks read after the latest verse, which come at the start of the next verse.
+    pending_breaks: Vec<Break>,
+    /// Headings read after the latest verse, which stand before the next verse.
+    pending_headings: Vec<PendingHeading>,
+    /// Heading currently being read and the depth of open elements within it.
+    current_heading: Option<(PendingHeading, usize)>,
+    /// Book whose `<div type="book">` is currently open.
+    current_book: Option<Book>,
+    introductions: Vec<(Book, String)>,
+    /// Introduction text currently being read and the depth of open elements within it.
+    current_introduction: Option<(String, usize)>,
+}
+impl VerseReader {
+    fn new(bounds: VerseIdRange) -> Self {
+        Self {
+            bounds,
+            strongs: None,
+            verses: Vec::new(),
+            current_verse: None,
+            combined_verse_ids: Vec::new(),
+            is_within_verse: false,
+            is_verse_milestone: false,
+            style_stack: Vec::new(),
+            open_quotation_milestones: Vec::new(),
+            current_note: None,
+            current_word: None,
+            headings: Vec::new(),
+            pending_breaks: Vec::new(),
+            pending_headings: Vec::new(),
+            current_heading: None,
+            current_book: None,
+            introductions: Vec::new(),
+            current_introduction: None,
+        }
+    }
+    /// Keeps only the verses with a word tagged with the given Strong's number.
+    fn with_strongs(mut self, strongs: StrongsNumber) -> Self {
+        self.strongs = Some(strongs);
+        self
+    }
+
+    /// Handles an XML event. Breaks when no more verses within bounds can follow.
+    fn handle_event(&mut self, element: XmlEvent) -> ControlFlow<()> {
+        match element {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => return self.handle_start_element(&name.local_name, &attributes),
+            XmlEvent::EndElement { name } => self.handle_end_element(&name.local_name),
+            XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+                self.handle_text(&text)
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+    fn handle_start_element(
+        &mut self,
+        name: &str,
+        attributes: &[OwnedAttribute],
+    ) -> ControlFlow<()> {
+        let style = element_style(
+            self.style_stack.last().copied().unwrap_or_default(),
+            name,
+            attributes,
+        );
+        self.style_stack.push(style);
+
+        if let Some((note, depth)) = &mut self.current_note {
+            *depth += 1;
+            if name == "reference" {
+                note.references.extend(
+                    find_xml_attribute(attributes, "osisRef")
+                        .into_iter()
+                        .flat_map(str::split_whitespace)
+                        .filter_map(VerseIdRange::from_osis_ref),
+                );
+            }
+            return ControlFlow::Continue(());
+        }
+        if let Some((_, depth)) = &mut self.current_introduction {
+            *depth += 1;
+            return ControlFlow::Continue(());
+        }
+        if let Some((_, depth)) = &mut self.current_heading {
+            *depth += 1;
+            return ControlFlow::Continue(());
+        }
+
+        match name {
+            "div" => match find_xml_attribute(attributes, "type") {
+                Some("book") => {
+                    if let Some(book) =
+                        find_xml_attribute(attributes, "osisID").and_then(Book::from_osis_id)
+                    {
+                        self.current_book = Some(book);
+                    }
+                }
+                Some("introduction") => self.current_introduction = Some((String::new(), 0)),
+                _ => {}
+            },
+            "title" => {
+                let heading = PendingHeading {
+                    heading_type: HeadingType::from_osis_type(find_xml_attribute(
+                        attributes, "type",
+                    )),
+                    canonical: find_xml_attribute(attributes, "canonical") == Some("true"),
+                    text: String::new(),
+                };
+                self.current_heading = Some((heading, 0));
+            }
+            "verse" => {
+                let is_start_milestone = find_xml_attribute(attributes, "sID").is_some();
+                let is_end_milestone = find_xml_attribute(attributes, "eID").is_some();
+                self.is_verse_milestone = is_start_milestone || is_end_milestone;
+
+                if is_end_milestone {
+                    self.finish_verse();
+                    return ControlFlow::Continue(());
+                }
+                let ids = find_xml_attribute(attributes, "osisID")
+                    .map(parse_osis_ids)
+                    .unwrap_or_default();
+                let Some(&first_id) = ids.first() else {
+                    return ControlFlow::Continue(());
+                };
+                if first_id > self.bounds.end() {
+                    return ControlFlow::Break(());
+                }
+                // A verse without an end milestone ends when the next one starts.
+                self.finish_verse();
+                self.is_within_verse = true;
+                let pending_breaks = std::mem::take(&mut self.pending_breaks);
+                let pending_headings = std::mem::take(&mut self.pending_headings);
+                let mut ids_within_bounds = ids.into_iter().filter(|id| self.bounds.contains(*id));
+                if let Some(id) = ids_within_bounds.next() {
+                    self.combined_verse_ids = ids_within_bounds.collect();
+                    self.headings.extend(
+                        pending_headings
+                            .into_iter()
+                            .map(|heading| heading.into_heading(id)),
+                    );
+                    let mut content = VerseContent::default();
+                    for pending_break in pending_breaks {
+                        content.push(Segment::Break(pending_break));
+                    }
+                    self.current_verse = Some((id, content));
+                }
+            }
+            "note" => {
+                let note = Note {
+                    note_type: find_xml_attribute(attributes, "type").map(NoteType::from_osis_type),
+                    text: String::new(),
+                    references: Vec::new(),
+                };
+                self.current_note = Some((note, 0));
+            }
+            "w" => {
+                self.current_word = Some(Word {
+                    text: String::new(),
+                    style: self.current_style(),
+                    strongs: find_xml_attribute(attributes, "lemma")
+                        .map(parse_strongs_numbers)
+                        .unwrap_or_default(),
+                    morphology: find_xml_attribute(attributes, "morph")
+                        .map(parse_morphology_codes)
+                        .unwrap_or_default(),
+                });
+            }
+            "q" => {
+                if let Some(start_id) = find_xml_attribute(attributes, "sID") {
+                    self.open_quotation_milestones
+                        .push((start_id.to_string(), is_words_of_christ(attributes)));
+                } else if let Some(end_id) = find_xml_attribute(attributes, "eID") {
+                    // Quotations may nest, so end the one with the matching ID.
+                    let index = self
+                        .open_quotation_milestones
+                        .iter()
+                        .rposition(|(start_id, _)| start_id == end_id);
+                    if let Some(index) = index {
+                        self.open_quotation_milestones.remove(index);
+                    } else {
+                        self.open_quotation_milestones.pop();
+                    }
+                }
+            }
+            // Separate text on different lines and paragraphs.
+            "p" => self.push_break(Break::Paragraph),
+            "lg" => self.push_break(Break::Stanza),
+            "l" => {
+                let level = find_xml_attribute(attributes, "level")
+                    .and_then(|level| level.parse::<u8>().ok())
+                    .unwrap_or(1);
+                self.push_break(Break::Line {
+                    indent: level.saturating_sub(1),
+                });
+            }
+            "lb" => self.push_break(Break::Line { indent: 0 }),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+    fn handle_end_element(&mut self, name: &str) {
+        self.style_stack.pop();
+
+        if let Some((_, depth)) = &mut self.current_note {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            let Some((mut note, _)) = self.current_note.take() else {
+                return;
+            };
+            note.text = collapse_whitespace(&note.text);
+            if let Some((_, content)) = &mut self.current_verse {
+                content.push(Segment::Note(note));
+            }
+            return;
+        }
+        if let Some((_, depth)) = &mut self.current_introduction {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            let Some((text, _)) = self.current_introduction.take() else {
+                return;
+            };
+            let book_within_bounds = self.current_book.filter(|book| {
+                (self.bounds.start().book()..=self.bounds.end().book()).contains(book)
+            });
+            if let Some(book) = book_within_bounds {
+                self.introductions.push((book, collapse_whitespace(&text)));
+            }
+            return;
+        }
+        if let Some((_, depth)) = &mut self.current_heading {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            let Some((mut heading, _)) = self.current_heading.take() else {
+                return;
+            };
+            heading.text = collapse_whitespace(&heading.text);
+            if heading.text.is_empty() {
+                return;
+            }
+            match &self.current_verse {
+                // Some headings, e.g. Psalm titles, may be within the first verse.
+                Some((id, _)) => self.headings.push(heading.into_heading(*id)),
+                None if !self.is_within_verse => self.pending_headings.push(heading),
+                // Skip headings within verses outside bounds.
+                None => {}
+            }
+            return;
+        }
+
+        match name {
+            // Container verses end with their end element.
+            "verse" if !self.is_verse_milestone => self.finish_verse(),
+            "w" => {
+                if let (Some(mut word), Some((_, content))) =
+                    (self.current_word.take(), &mut self.current_verse)
+                {
+                    word.text = collapse_whitespace(&word.text);
+                    content.push(Segment::Word(word));
+                }
+            }
+            "l" | "lg" | "p" => self.handle_text(" "),
+            _ => {}
+        }
+    }
+    fn handle_text(&mut self, text: &str) {
+        if let Some((note, _)) = &mut self.current_note {
+            note.text.push_str(text);
+            return;
+        }
+        if let Some((introduction, _)) = &mut self.current_introduction {
+            introduction.push_str(text);
+            return;
+        }
+        if let Some((heading, _)) = &mut self.current_heading {
+            heading.text.push_str(text);
+            return;
+        }
+        if let Some(word) = &mut self.current_word {
+            word.text.push_str(text);
+            return;
+        }
+        let style = self.current_style();
+        if let Some((_, content)) = &mut self.current_verse {
+            content.push_text(text, style);
+        }
+    }
+    /// Adds a break to the verse currently being read, or to the start of the next verse.
+    fn push_break(&mut self, layout_break: Break) {
+        match &mut self.current_verse {
+            Some((_, content)) => content.push(Segment::Break(layout_break)),
+            None if !self.is_within_verse => self.pending_breaks.push(layout_break),
+            // Skip breaks within verses outside bounds.
+            None => {}
+        }
+        // Separate text on different lines and paragraphs also in plain text.
+        self.handle_text(" ");
+    }
+    /// Returns the style of text at the current position.
+    fn current_style(&self) -> TextStyle {
+        let mut style = self.style_stack.last().copied().unwrap_or_default();
+        style.quotation |= !self.open_quotation_milestones.is_empty();
+        style.words_of_christ |= self
+            .open_quotation_milestones
+            .iter()
+            .any(|(_, is_words_of_christ)| *is_words_of_christ);
+        style
+    }
+    /// Adds the verse currently being read, if any, to the verses read.
+    fn finish_verse(&mut self) {
+        self.is_within_verse = false;
+        if let Some((id, mut content)) = self.current_verse.take() {
+            if self
+                .strongs
+                .is_none_or(|strongs| content.contains_strongs(strongs))
+            {
+                content.collapse_whitespace();
+                let ids = std::iter::once(id).chain(std::mem::take(&mut self.combined_verse_ids));
+                self.verses.extend(ids.map(|id| (id, content.clone())));
+            }
+        }
+    }
+    fn finish(mut self) -> Passage {
+        self.finish_verse();
+        Passage {
+            verses: self.verses,
+            headings: self.headings,
+            introductions: self.introductions,
+        }
+    }
+}
+
+/// Reads the metadata of the text from the `<work>` elements of the header,
+/// which precedes the content, so the rest of the document is not parsed.
+pub(crate) fn read_metadata(reader: impl Read) -> Result<SourceMetadata, SourceError> {
+    let mut parser = xml::EventReader::new(reader);
+    let mut work_id = None;
+    let mut works = vec![];
+    // Element of the current work being read and its text so far.
+    let mut current_field: Option<(String, String)> = None;
+    loop {
+        match parser.next()? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match name.local_name.as_str() {
+                "osisText" => {
+                    work_id = find_xml_attribute(&attributes, "osisIDWork").map(str::to_string)
+                }
+                "work" => works.push(SourceMetadata {
+                    abbreviation: find_xml_attribute(&attributes, "osisWork").map(str::to_string),
+                    ..SourceMetadata::default()
+                }),
+                "div" | "chapter" | "verse" => break,
+                name if !works.is_empty() && current_field.is_none() => {
+                    current_field = Some((name.to_string(), String::new()))
+                }
+                _ => {}
+            },
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                if let Some((_, field_text)) = &mut current_field {
+                    field_text.push_str(&text);
+                }
+            }
+            XmlEvent::EndElement { name } => match name.local_name.as_str() {
+                "header" => break,
+                "work" => current_field = None,
+                name if current_field
+                    .as_ref()
+                    .is_some_and(|(field_name, _)| field_name == name) =>
+                {
+                    let Some((field_name, text)) = current_field.take() else {
+                        continue;
+                    };
+                    if let Some(work) = works.last_mut() {
+                        set_work_field(work, &field_name, collapse_whitespace(&text));
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+
+    let position = works
+        .iter()
+        .position(|work| work_id.is_some() && work.abbreviation == work_id)
+        .unwrap_or(0);
+    let mut metadata = works.into_iter().nth(position).unwrap_or_default();
+    if metadata.abbreviation.is_none() {
+        metadata.abbreviation = work_id;
+    }
+    Ok(metadata)
+}
+/// Sets a field of metadata by an element of `<work>`, unless an earlier element has set it.
+fn set_work_field(work: &mut SourceMetadata, name: &str, value: String) {
+    let field = match name {
+        "title" => &mut work.title,
+        "language" => &mut work.language,
+        "publisher" => &mut work.publisher,
+        "rights" => &mut work.rights,
+        "refSystem" => &mut work.versification,
+        "identifier" => &mut work.identifier,
+        _ => return,
+    };
+    if field.is_none() && !value.is_empty() {
+        // Versifications are given as e.g. "Bible.KJV".
+        let value = match name {
+            "refSystem" => value.strip_prefix("Bible.").unwrap_or(&value).to_string(),
+            _ => value,
+        };
+        *field = Some(value);
+    }
+}
+/// Returns the text style within an element, given the style of its parent element.
+fn element_style(parent_style: TextStyle, name: &str, attributes: &[OwnedAttribute]) -> TextStyle {
+    let mut style = parent_style;
+    let element_type = find_xml_attribute(attributes, "type");
+    match name {
+        "transChange" if element_type == Some("added") => style.added = true,
+        "divineName" => style.divine_name = true,
+        "hi" if element_type == Some("italic") => style.italic = true,
+        // Quotation milestones are empty elements, so they don't contain any text themselves.
+        "q" if find_xml_attribute(attributes, "sID").is_none()
+            && find_xml_attribute(attributes, "eID").is_none() =>
+        {
+            style.quotation = true;
+            style.words_of_christ |= is_words_of_christ(attributes);
+        }
+        _ => {}
+    }
+    style
+}
+/// Returns whether a quotation element marks words of Christ,
+/// either by its speaker or by being marked for red letters.
+fn is_words_of_christ(attributes: &[OwnedAttribute]) -> bool {
+    find_xml_attribute(attributes, "who") == Some("Jesus")
+        || find_xml_attribute(attributes, "marker") == Some("red")
+}
+pub(crate) fn find_xml_attribute<'a>(
+    attributes: &'a [OwnedAttribute],
+    name: &str,
+) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name == name)
+        .map(|attribute| attribute.value.as_str())
+}
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+/// Parses Strong's numbers of a `lemma` attribute, which may list many lemmas separated by spaces,
+/// e.g. "strong:G2532 lemma.TR:και". Lemmas of other kinds are ignored.
+fn parse_strongs_numbers(lemma: &str) -> Vec<StrongsNumber> {
+    lemma
+        .split_whitespace()
+        .filter_map(|lemma| lemma.strip_prefix("strong:"))
+        .filter_map(StrongsNumber::parse)
+        .collect()
+}
+/// Parses morphology codes of a `morph` attribute, which may list many codes separated by spaces,
+/// each optionally prefixed by its scheme, e.g. "robinson:N-ASF".
+pub(crate) fn parse_morphology_codes(morph: &str) -> Vec<MorphologyCode> {
+    morph
+        .split_whitespace()
+        .map(|code| match code.split_once(':') {
+            Some((scheme, code)) => MorphologyCode {
+                scheme: Some(scheme.to_string()),
+                code: code.to_string(),
+            },
+            None => MorphologyCode {
+                scheme: None,
+                code: code.to_string(),
+            },
+        })
+        .collect()
+}
+/// Parses the verse IDs of an `osisID` attribute,
+/// which may list many verse IDs separated by spaces when verses are combined.
+fn parse_osis_ids(osis_id: &str) -> Vec<VerseId> {
+    osis_id
+        .split_whitespace()
+        .filter_map(VerseId::from_osis_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_reference;
+
+    const MILESTONE_OSIS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+  <osisText osisIDWork="KJV">
+    <div type="book" osisID="Ps">
+      <chapter osisID="Ps.1" sID="Ps.1"/>
+      <lg>
+        <l><verse osisID="Ps.1.1" sID="Ps.1.1"/>Blessed <transChange type="added">is</transChange> the man</l>
+        <l level="2">that walketh not in the counsel of the ungodly,<verse eID="Ps.1.1"/></l>
+      </lg>
+      <p>
+        <verse osisID="Ps.1.2" sID="Ps.1.2"/>But his delight <note type="study">Or, law</note>is in the law of the
+      </p>
+      <p>
+        <q who="Jesus">LORD;</q><verse eID="Ps.1.2"/>
+        <verse osisID="Ps.1.3" sID="Ps.1.3"/>And he shall be like a tree.<verse eID="Ps.1.3"/>
+      </p>
+      <chapter eID="Ps.1"/>
+      <chapter osisID="Ps.2" sID="Ps.2"/>
+      <verse osisID="Ps.2.1" sID="Ps.2.1"/>Why do the heathen rage?<verse eID="Ps.2.1"/>
+      <chapter eID="Ps.2"/>
+    </div>
+  </osisText>
+</osis>"#;
+
+    const RED_LETTER_OSIS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+  <osisText osisIDWork="KJV">
+    <div type="book" osisID="John">
+      <chapter osisID="John.11">
+        <verse osisID="John.11.34">And said, <q who="Jesus">Where have ye laid him?</q> They said unto him, Lord, come and see.</verse>
+        <verse osisID="John.11.35">Jesus wept.</verse>
+        <verse osisID="John.11.39">Jesus said, <q marker="red" sID="q1"/>Take ye away the stone.<q eID="q1"/></verse>
+        <verse osisID="John.11.40">Jesus saith unto her, <q marker="red" sID="q2"/>Said I not unto thee, that, if thou <transChange type="added">wouldest</transChange> believe, thou shouldest see the glory of God?<q eID="q2"/></verse>
+        <verse osisID="John.11.41">And Jesus lifted up <transChange type="added">his</transChange> eyes, and said, <q marker="red" sID="q3"/>Father, I thank thee that thou hast heard me.</verse>
+        <verse osisID="John.11.42">And I knew that thou hearest me always.<q eID="q3"/></verse>
+        <verse osisID="John.11.43">He cried with a loud voice, <q who="Jesus">Lazarus,</q> he said, <q who="Jesus">come forth.</q></verse>
+      </chapter>
+    </div>
+  </osisText>
+</osis>"#;
+
+    const TAGGED_OSIS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+  <osisText osisIDWork="KJV">
+    <div type="book" osisID="1John">
+      <chapter osisID="1John.4">
+        <verse osisID="1John.4.7"><w lemma="strong:G27" morph="robinson:A-NPM">Beloved</w>, let us <w lemma="strong:G25" morph="robinson:V-PAS-1P">love</w> one another: for <w lemma="strong:G26 lemma.TR:αγαπη" morph="robinson:N-NSF">love</w> is of God</verse>
+        <verse osisID="1John.4.8">He that loveth not knoweth not God; for God is <w lemma="strong:G26">love</w>.</verse>
+      </chapter>
+    </div>
+    <div type="book" osisID="Jude">
+      <chapter osisID="Jude.1">
+        <verse osisID="Jude.1.21">Keep yourselves in the <w lemma="strong:G0026">love</w> of God.</verse>
+        <verse osisID="Jude.1.22">And of some have compassion, making a difference:</verse>
+      </chapter>
+    </div>
+  </osisText>
+</osis>"#;
+
+    fn contents(source: &OsisSource, reference: &str) -> Vec<(u8, u8, String)> {
+        source
+            .find_content(&parse_reference(reference).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|reference| {
+                (
+                    reference.chapter,
+                    reference.number,
+                    reference.content.plain_text(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn find_content_of_milestone_verses() {
+        let source = OsisSource::from_data(MILESTONE_OSIS.into());
+
+        assert_eq!(
+            contents(&source, "Ps 1:1-2"),
+            [
+                (
+                    1,
+                    1,
+                    String::from(
+                        "Blessed is the man that walketh not in the counsel of the ungodly,"
+                    )
+                ),
+                (
+                    1,
+                    2,
+                    String::from("But his delight is in the law of the LORD;")
+                ),
+            ]
+        );
+        assert_eq!(contents(&source, "Ps 1:3+").len(), 1);
+        assert_eq!(contents(&source, "Ps 1").len(), 3);
+        assert_eq!(
+            contents(&source, "Ps 1:3-2:1"),
+            [
+                (1, 3, String::from("And he shall be like a tree.")),
+                (2, 1, String::from("Why do the heathen rage?")),
+            ]
+        );
+        assert_eq!(contents(&source, "Ps 3"), []);
+    }
+    #[test]
+    fn find_content_of_container_verses() {
+        let source = OsisSource::from_data(
+            r#"<osis><osisText><div type="book" osisID="John"><chapter osisID="John.3">
+                <verse osisID="John.3.1">There was a man of the Pharisees, named Nicodemus, a ruler of the Jews:</verse>
+                <verse osisID="John.3.2">The same came to Jesus by night.</verse>
+            </chapter><chapter osisID="John.4">
+                <verse osisID="John.4.1 John.4.2">When therefore the Lord knew, (Though Jesus himself baptized not,)</verse>
+            </chapter></div></osisText></osis>"#
+                .into(),
+        );
+
+        assert_eq!(
+            contents(&source, "John 3:2"),
+            [(3, 2, String::from("The same came to Jesus by night."))]
+        );
+        assert!(source
+            .find_content(&parse_reference("John 3:1-5").unwrap())
+            .is_err());
+
+        // Combined verses are found under each of their verse IDs.
+        let combined = "When therefore the Lord knew, (Though Jesus himself baptized not,)";
+        assert_eq!(
+            contents(&source, "John 4:2"),
+            [(4, 2, String::from(combined))]
+        );
+        assert_eq!(
+            contents(&source, "John 4:1-2"),
+            [
+                (4, 1, String::from(combined)),
+                (4, 2, String::from(combined))
+            ]
+        );
+    }
+    #[test]
+    fn find_styled_content_notes_and_breaks() {
+        let source = OsisSource::from_data(MILESTONE_OSIS.into());
+        let verses = source
+            .find_content(&parse_reference("Psalms 1:1-2").unwrap())
+            .unwrap();
+        let text = |text: &str, style: TextStyle| Segment::Text {
+            text: String::from(text),
+            style,
+        };
+
+        assert_eq!(
+            verses[0].content.segments(),
+            [
+                Segment::Break(Break::Stanza),
+                Segment::Break(Break::Line { indent: 0 }),
+                text("Blessed ", TextStyle::default()),
+                text(
+                    "is",
+                    TextStyle {
+                        added: true,
+                        ..TextStyle::default()
+                    }
+                ),
+                text(" the man ", TextStyle::default()),
+                Segment::Break(Break::Line { indent: 1 }),
+                text(
+                    "that walketh not in the counsel of the ungodly,",
+                    TextStyle::default()
+                ),
+            ]
+        );
+        assert_eq!(
+            verses[1].content.segments(),
+            [
+                Segment::Break(Break::Paragraph),
+                text("But his delight ", TextStyle::default()),
+                Segment::Note(Note {
+                    note_type: Some(NoteType::Study),
+                    text: String::from("Or, law"),
+                    references: vec![],
+                }),
+                text("is in the law of the ", TextStyle::default()),
+                Segment::Break(Break::Paragraph),
+                text(
+                    "LORD;",
+                    TextStyle {
+                        quotation: true,
+                        words_of_christ: true,
+                        ..TextStyle::default()
+                    }
+                ),
+            ]
+        );
+    }
+    #[test]
+    fn find_words_of_christ() {
+        let source = OsisSource::from_data(RED_LETTER_OSIS.into());
+        let verses = source
+            .find_content(&parse_reference("John 11:34-40").unwrap())
+            .unwrap();
+        assert_eq!(
+            verses
+                .iter()
+                .map(|verse| verse.content.words_of_christ())
+                .collect::<Vec<_>>(),
+            [
+                vec!["Where have ye laid him?"],
+                vec![],
+                vec!["Take ye away the stone."],
+                vec!["Said I not unto thee, that, if thou wouldest believe, thou shouldest see the glory of God?"],
+            ]
+        );
+
+        let words_of_christ = source
+            .find_words_of_christ(&parse_reference("John 11").unwrap())
+            .unwrap();
+        assert_eq!(
+            words_of_christ
+                .iter()
+                .map(|verse| (verse.number, verse.content.plain_text()))
+                .collect::<Vec<_>>(),
+            [
+                (34, String::from("Where have ye laid him?")),
+                (39, String::from("Take ye away the stone.")),
+                (40, String::from("Said I not unto thee, that, if thou wouldest believe, thou shouldest see the glory of God?")),
+                // The quotation crosses from one verse to the next.
+                (41, String::from("Father, I thank thee that thou hast heard me.")),
+                (42, String::from("And I knew that thou hearest me always.")),
+                (43, String::from("Lazarus, come forth.")),
+            ]
+        );
+    }
+    #[test]
+    fn find_words_with_strongs_numbers_and_morphology() {
+        let source = OsisSource::from_data(TAGGED_OSIS.into());
+        let verses = source
+            .find_content(&parse_reference("1 John 4:7").unwrap())
+            .unwrap();
+        let content = &verses[0].content;
+        assert_eq!(
+            content.plain_text(),
+            "Beloved, let us love one another: for love is of God"
+        );
+
+        let words = content.words().collect::<Vec<_>>();
+        assert_eq!(
+            words
+                .iter()
+                .map(|word| (word.text.as_str(), word.strongs.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("Beloved", vec![StrongsNumber::Greek(27)]),
+                ("love", vec![StrongsNumber::Greek(25)]),
+                ("love", vec![StrongsNumber::Greek(26)]),
+            ]
+        );
+        assert_eq!(
+            words[2].morphology,
+            [MorphologyCode {
+                scheme: Some(String::from("robinson")),
+                code: String::from("N-NSF")
+            }]
+        );
+
+        assert_eq!(
+            source
+                .find_verses_with_strongs(StrongsNumber::Greek(26))
+                .unwrap()
+                .iter()
+                .map(|(id, _)| id.to_string())
+                .collect::<Vec<_>>(),
+            ["1John.4.7", "1John.4.8", "Jude.1.21"]
+        );
+    }
+    #[test]
+    fn find_notes_with_anchors_and_cross_references() {
+        let source = OsisSource::from_data(
+            r#"<osis><osisText><div type="book" osisID="John"><chapter osisID="John.1">
+            <verse osisID="John.1.1">In the beginning<note type="crossReference" osisRef="John.1.1"><reference osisRef="Gen.1.1">Gen. 1:1</reference>; <reference osisRef="1John.1.1-1John.1.2">1 John 1:1-2</reference></note> was the Word<note type="translation">Or, Logos</note>.</verse>
+            </chapter></div></osisText></osis>"#
+                .into(),
+        );
+        let verses = source
+            .find_content(&parse_reference("John 1:1").unwrap())
+            .unwrap();
+        let content = &verses[0].content;
+        assert_eq!(content.plain_text(), "In the beginning was the Word.");
+
+        let notes = content.notes();
+        assert_eq!(
+            notes
+                .iter()
+                .map(|(anchor, note)| (*anchor, note.note_type.clone(), note.text.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "In the beginning".len(),
+                    Some(NoteType::CrossReference),
+                    "Gen. 1:1; 1 John 1:1-2"
+                ),
+                (
+                    "In the beginning was the Word".len(),
+                    Some(NoteType::Translation),
+                    "Or, Logos"
+                ),
+            ]
+        );
+        assert_eq!(
+            notes[0].1.references,
+            [
+                VerseIdRange::single(VerseId::new(Book::Genesis, 1, 1)),
+                VerseIdRange::new(
+                    VerseId::new(Book::FirstJohn, 1, 1),
+                    VerseId::new(Book::FirstJohn, 1, 2)
+                )
+                .unwrap(),
+            ]
+        );
+    }
+    #[test]
+    fn find_headings_psalm_titles_and_introductions() {
+        let source = OsisSource::from_data(
+            r#"<osis><osisText>
+            <div type="book" osisID="Ps">
+              <title type="main">THE BOOK OF PSALMS</title>
+              <div type="introduction"><p>The Psalms are <hi type="italic">songs</hi>
+                of Israel.</p></div>
+              <chapter osisID="Ps.3" sID="Ps.3"/>
+              <title type="chapter">PSALM 3</title>
+              <title type="psalm" canonical="true">A Psalm of David, when he fled from Absalom his son.</title>
+              <verse osisID="Ps.3.1" sID="Ps.3.1"/>LORD, how are they increased that trouble me!<verse eID="Ps.3.1"/>
+              <title>Trust in the LORD</title>
+              <verse osisID="Ps.3.2" sID="Ps.3.2"/>Many there be which say of my soul,<verse eID="Ps.3.2"/>
+              <chapter eID="Ps.3"/>
+            </div>
+            </osisText></osis>"#
+                .into(),
+        );
+
+        let headings = source
+            .find_headings(&parse_reference("Psalms 3").unwrap())
+            .unwrap();
+        assert_eq!(
+            headings
+                .iter()
+                .map(|heading| (heading.heading_type.clone(), heading.verse_id.verse()))
+                .collect::<Vec<_>>(),
+            [
+                (HeadingType::Book, 1),
+                (HeadingType::Chapter, 1),
+                (HeadingType::Psalm, 1),
+                (HeadingType::Section, 2),
+            ]
+        );
+        assert_eq!(headings[3].text, "Trust in the LORD");
+        assert!(source
+            .find_headings(&parse_reference("Psalms 3:2").unwrap())
+            .unwrap()
+            .iter()
+            .all(|heading| heading.heading_type == HeadingType::Section));
+        assert_eq!(
+            contents(&source, "Psalms 3:1"),
+            [(
+                3,
+                1,
+                String::from("LORD, how are they increased that trouble me!")
+            )]
+        );
+
+        assert_eq!(
+            source.find_psalm_title(3).unwrap().as_deref(),
+            Some("A Psalm of David, when he fled from Absalom his son.")
+        );
+        assert_eq!(
+            source.find_introduction(Book::Psalms).unwrap().as_deref(),
+            Some("The Psalms are songs of Israel.")
+        );
+        assert_eq!(source.find_introduction(Book::Proverbs).unwrap(), None);
+    }
+    #[test]
+    fn skip_headings_and_breaks_within_verses_outside_bounds() {
+        let source = OsisSource::from_data(
+            r#"<osis><osisText><div type="book" osisID="Ps"><chapter osisID="Ps.3">
+            <verse osisID="Ps.3.1">LORD, how are they increased that trouble me!
+              <title>Within verse 1</title><lb/>many are they that rise up against me.</verse>
+            <verse osisID="Ps.3.2">Many there be which say of my soul,</verse>
+            </chapter></div></osisText></osis>"#
+                .into(),
+        );
+
+        assert_eq!(
+            source
+                .find_headings(&parse_reference("Psalms 3:2").unwrap())
+                .unwrap(),
+            []
+        );
+        let verses = source
+            .find_content(&parse_reference("Psalms 3:2").unwrap())
+            .unwrap();
+        assert!(verses[0]
+            .content
+            .segments()
+            .iter()
+            .all(|segment| !matches!(segment, Segment::Break(_))));
+    }
+    #[test]
+    fn index_source_with_same_content_as_streaming() {
+        let source = OsisSource::from_data(MILESTONE_OSIS.into());
+        let indexed_source = source.index().unwrap();
+        assert_eq!(indexed_source.index().len(), 4);
+
+        for reference in ["Psalms 1", "Psalms 1:2-3", "Psalms 2:1", "Psalms 1-2"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                indexed_source.find_content(&parse_result).unwrap(),
+                source.find_content(&parse_result).unwrap()
+            );
+        }
+    }
+    #[test]
+    fn read_metadata_of_work_in_header() {
+        let source = OsisSource::from_data(
+            br#"<osis><osisText osisIDWork="KJV" osisRefWork="Bible">
+            <header>
+              <work osisWork="Strong"><title>Strong's Concordance</title></work>
+              <work osisWork="KJV">
+                <title>King James Version (1769)</title>
+                <identifier type="OSIS">Bible.KJV</identifier>
+                <language type="IETF">en</language>
+                <rights>Public Domain</rights>
+                <refSystem>Bible.KJV</refSystem>
+              </work>
+            </header>
+            <div type="book" osisID="John"><verse osisID="John.11.35">Jesus wept.</verse></div>
+            </osisText></osis>"#
+                .to_vec(),
+        );
+        let metadata = SourceMetadata {
+            title: Some("King James Version (1769)".to_string()),
+            abbreviation: Some("KJV".to_string()),
+            language: Some("en".to_string()),
+            publisher: None,
+            rights: Some("Public Domain".to_string()),
+            versification: Some("KJV".to_string()),
+            identifier: Some("Bible.KJV".to_string()),
+        };
+        assert_eq!(source.metadata(), &metadata);
+        assert_eq!(source.index().unwrap().metadata(), &metadata);
+
+        // Without a header, only the abbreviation of the work is known.
+        let source = OsisSource::from_data(MILESTONE_OSIS.into());
+        assert_eq!(
+            source.metadata(),
+            &SourceMetadata {
+                abbreviation: Some("KJV".to_string()),
+                ..SourceMetadata::default()
+            }
+        );
+    }
+}
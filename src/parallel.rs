@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use crate::{Missing, ReferenceParseResult, Source, SourceError, VerseContent};
+
+/// Verse of a reference found in many sources, e.g. in different translations.
+#[derive(Debug, PartialEq)]
+pub struct ParallelVerse {
+    pub chapter: u8,
+    pub number: u8,
+    /// Content of the verse in each source, in the order of the sources,
+    /// or `None` if a source doesn't have the verse.
+    pub contents: Vec<Option<VerseContent>>,
+}
+impl ParallelVerse {
+    /// Returns whether every source has the verse.
+    pub fn is_in_all_sources(&self) -> bool {
+        self.contents.iter().all(Option::is_some)
+    }
+}
+
+/// Content found for a reference in many sources, along with the parts of the reference
+/// that each source doesn't have.
+#[derive(Debug, PartialEq)]
+pub struct ParallelLookup {
+    pub verses: Vec<ParallelVerse>,
+    /// Missing parts of the reference in each source, in the order of the sources,
+    /// determined like in [`crate::Lookup`].
+    pub missing: Vec<Vec<Missing>>,
+}
+
+/// Finds the content of a reference in many sources and aligns it verse by verse,
+/// in canonical order.
+///
+/// A verse is included if any of the sources has it, and its content is `None` for the sources
+/// that don't. Verses that none of the sources have are left out of the verses,
+/// but like all missing verses, they are listed in the missing parts of each source.
+///
+/// Verses are looked up like with [`Source::look_up`], so a range of verses that ends past
+/// the last verse of its chapter in a source is not an error.
+pub fn find_parallel<'a, S: Source + ?Sized + 'a>(
+    sources: impl IntoIterator<Item = &'a S>,
+    parse_result: &ReferenceParseResult,
+) -> Result<ParallelLookup, SourceError> {
+    let lookups = sources
+        .into_iter()
+        .map(|source| source.look_up(parse_result))
+        .collect::<Result<Vec<_>, _>>()?;
+    let source_count = lookups.len();
+    let mut verses = BTreeMap::new();
+    let mut missing = Vec::with_capacity(source_count);
+    for (position, lookup) in lookups.into_iter().enumerate() {
+        missing.push(lookup.missing);
+        for reference in lookup.references {
+            let contents = verses
+                .entry((reference.chapter, reference.number))
+                .or_insert_with(|| vec![None; source_count]);
+            contents[position] = Some(reference.content);
+        }
+    }
+    let verses = verses
+        .into_iter()
+        .map(|((chapter, number), contents)| ParallelVerse {
+            chapter,
+            number,
+            contents,
+        })
+        .collect();
+    Ok(ParallelLookup { verses, missing })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_reference, Book, IndexedSource, VerseId, VerseIndex};
+
+    fn source(verses: &[(u8, u8, &str)]) -> IndexedSource {
+        let verses = verses
+            .iter()
+            .map(|(chapter, verse, text)| {
+                (
+                    VerseId::new(Book::John, *chapter, *verse),
+                    VerseContent::from(*text),
+                )
+            })
+            .collect();
+        IndexedSource::new(VerseIndex::new(verses), vec![], vec![])
+    }
+    fn plain_texts(verses: &[ParallelVerse]) -> Vec<(u8, Vec<Option<String>>)> {
+        verses
+            .iter()
+            .map(|verse| {
+                let texts = verse
+                    .contents
+                    .iter()
+                    .map(|content| content.as_ref().map(VerseContent::plain_text))
+                    .collect();
+                (verse.number, texts)
+            })
+            .collect()
+    }
+
+    fn missing_texts(missing: &[Missing]) -> Vec<String> {
+        missing.iter().map(Missing::to_string).collect()
+    }
+
+    #[test]
+    fn align_verses_of_sources() {
+        let sources = [
+            source(&[(3, 16, "For God so loved"), (3, 17, "For God sent not")]),
+            source(&[(3, 16, "Sillä niin on Jumala"), (3, 18, "Joka uskoo")]),
+        ];
+        let lookup = find_parallel(&sources, &parse_reference("John 3:16-18").unwrap()).unwrap();
+        let verses = lookup.verses;
+        assert_eq!(
+            plain_texts(&verses),
+            [
+                (
+                    16,
+                    vec![
+                        Some("For God so loved".to_string()),
+                        Some("Sillä niin on Jumala".to_string())
+                    ]
+                ),
+                (17, vec![Some("For God sent not".to_string()), None]),
+                (18, vec![None, Some("Joka uskoo".to_string())]),
+            ]
+        );
+        assert!(verses[0].is_in_all_sources());
+        assert!(!verses[1].is_in_all_sources());
+    }
+    #[test]
+    fn mark_verses_missing_from_sources_without_any() {
+        let sources: [&dyn Source; 3] = [
+            &source(&[(3, 36, "He that believeth on the Son")]),
+            &source(&[(3, 36, "Joka uskoo Poikaan"), (4, 1, "Kun nyt Herra")]),
+            &source(&[]),
+        ];
+        // The range ends past the last verse of chapter 3, which is not an error.
+        let lookup = find_parallel(sources, &parse_reference("John 3:36-40").unwrap()).unwrap();
+        assert_eq!(
+            plain_texts(&lookup.verses),
+            [(
+                36,
+                vec![
+                    Some("He that believeth on the Son".to_string()),
+                    Some("Joka uskoo Poikaan".to_string()),
+                    None
+                ]
+            )]
+        );
+        assert_eq!(
+            lookup
+                .missing
+                .iter()
+                .map(|missing| missing_texts(missing))
+                .collect::<Vec<_>>(),
+            [
+                vec!["John 3:37-40".to_string()],
+                vec!["John 3:37-40".to_string()],
+                vec!["John 3:36-40".to_string()]
+            ]
+        );
+
+        // Verses and books that none of the sources have are still reported missing.
+        let lookup = find_parallel(sources, &parse_reference("John 3:99").unwrap()).unwrap();
+        assert_eq!(lookup.verses, []);
+        assert!(lookup
+            .missing
+            .iter()
+            .all(|missing| missing_texts(missing) == ["John 3:99"]));
+        let lookup = find_parallel(sources, &parse_reference("Jude 1:3").unwrap()).unwrap();
+        assert!(lookup
+            .missing
+            .iter()
+            .all(|missing| missing_texts(missing) == ["Jude 1:3"]));
+    }
+}
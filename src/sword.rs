@@ -0,0 +1,677 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::ZlibDecoder;
+
+use crate::{
+    osis::collapse_whitespace, Book, IndexedSource, OsisSource, Reference, ReferenceParseResult,
+    Source, SourceError, SourceMetadata, Testament, VerseContent, VerseId, VerseIndex,
+};
+
+/// Represents a Bible text installed as a CrossWire SWORD module, i.e. a module configuration
+/// in `mods.d/<module>.conf` and data files in the directory given by its `DataPath`,
+/// e.g. `modules/texts/ztext/kjv/`.
+///
+/// The following module drivers (`ModDrv`) are supported:
+/// - `RawText` and `RawText4`, where verses are stored uncompressed.
+/// - `zText` and `zText4`, where verses are stored in zlib-compressed blocks (`CompressType=ZIP`).
+///
+/// Verses are located by the KJV versification, which is the default of SWORD modules.
+/// Modules with the KJVA versification are read as well, since the books of the Apocrypha
+/// follow the books of the Old Testament, but other versifications are not supported.
+///
+/// Verses in OSIS markup (`SourceType=OSIS`) are read like in [`OsisSource`], so they may have
+/// headings, tagged words, notes and breaks. Verses in other markups are read as plain text
+/// without notes. Book introductions stored in modules are read as well.
+///
+/// The whole module is read when created.
+///
+/// See https://wiki.crosswire.org/DevTools:conf_Files for more information.
+pub struct SwordSource {
+    conf: ModuleConf,
+    source: IndexedSource,
+}
+impl Source for SwordSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+    fn metadata(&self) -> &SourceMetadata {
+        self.source.metadata()
+    }
+}
+impl SwordSource {
+    /// Opens the module with the given name, ignoring letter case, installed in the given
+    /// SWORD directory, i.e. the directory containing `mods.d` and `modules`.
+    pub fn open(sword_path: &Path, module_name: &str) -> Result<Self, SourceError> {
+        let conf_directory = sword_path.join("mods.d");
+        let entries = fs::read_dir(&conf_directory)
+            .map_err(|error| SourceError::io(&conf_directory, error))?;
+        let mut conf_paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "conf")
+            })
+            .collect::<Vec<_>>();
+        conf_paths.sort();
+
+        for conf_path in conf_paths {
+            // Configuration files of other modules that can't be read don't prevent opening
+            // this one, like they don't prevent scanning a library.
+            let Ok(conf) = ModuleConf::read(&conf_path) else {
+                continue;
+            };
+            if conf.name.eq_ignore_ascii_case(module_name) {
+                return Self::from_conf(conf, sword_path);
+            }
+        }
+        Err(SourceError::io(
+            conf_directory,
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("SWORD module not found: {module_name}"),
+            ),
+        ))
+    }
+    /// Opens the module of the given configuration file, which is in the `mods.d` directory
+    /// of a SWORD directory.
+    pub fn from_conf_file(conf_path: &Path) -> Result<Self, SourceError> {
+        Self::from_conf(ModuleConf::read(conf_path)?, sword_directory(conf_path)?)
+    }
+
+    /// Returns the name of the module, e.g. "KJV".
+    pub fn module_name(&self) -> &str {
+        &self.conf.name
+    }
+    /// Returns the first value of the given key in the module configuration,
+    /// e.g. "Description" or "Lang".
+    pub fn conf_value(&self, key: &str) -> Option<&str> {
+        self.conf.get(key)
+    }
+
+    /// Returns the parsed source, e.g. to find headings, Psalm titles and introductions.
+    pub fn as_index(&self) -> &IndexedSource {
+        &self.source
+    }
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+
+    fn from_conf(conf: ModuleConf, sword_path: &Path) -> Result<Self, SourceError> {
+        let versification = conf.get("Versification").unwrap_or("KJV");
+        if !["KJV", "KJVA"].contains(&versification) {
+            return Err(SourceError::Unsupported(format!(
+                "Versification of SWORD module {}: {versification}",
+                conf.name
+            )));
+        }
+        let driver = match conf.get("ModDrv").map(str::to_lowercase).as_deref() {
+            Some("rawtext") => Driver::RawText { size_len: 2 },
+            Some("rawtext4") => Driver::RawText { size_len: 4 },
+            Some("ztext") => Driver::ZText { size_len: 2 },
+            Some("ztext4") => Driver::ZText { size_len: 4 },
+            driver => {
+                return Err(SourceError::Unsupported(format!(
+                    "Driver of SWORD module {}: {}",
+                    conf.name,
+                    driver.unwrap_or_default()
+                )))
+            }
+        };
+        if let Driver::ZText { .. } = driver {
+            let compression = conf.get("CompressType").unwrap_or("ZIP");
+            if !compression.eq_ignore_ascii_case("ZIP") {
+                return Err(SourceError::Unsupported(format!(
+                    "Compression of SWORD module {}: {compression}",
+                    conf.name
+                )));
+            }
+        }
+        let data_path = conf.data_path(sword_path)?;
+        let is_utf8 = conf
+            .get("Encoding")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("UTF-8"));
+        let is_osis = conf
+            .get("SourceType")
+            .is_some_and(|source_type| source_type.eq_ignore_ascii_case("OSIS"));
+
+        let mut entries = vec![];
+        for testament in [Testament::Old, Testament::New] {
+            let Some(mut testament_data) = TestamentData::open(&data_path, testament, driver)?
+            else {
+                continue;
+            };
+            for (book, chapter_verse, index) in entry_indexes(testament) {
+                let entry = testament_data.entry(index)?;
+                let text = match is_utf8 {
+                    true => String::from_utf8_lossy(&entry).into_owned(),
+                    false => entry.iter().map(|byte| char::from(*byte)).collect(),
+                };
+                let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+                if !text.is_empty() {
+                    entries.push((book, chapter_verse, text.to_string()));
+                }
+            }
+        }
+
+        let source = match is_osis {
+            true => OsisSource::from_data(osis_document(&entries).into_bytes()).index()?,
+            false => {
+                let mut verses = vec![];
+                let mut introductions = vec![];
+                for (book, chapter_verse, text) in entries {
+                    let text = strip_markup(&text);
+                    match chapter_verse {
+                        Some((chapter, verse)) => verses.push((
+                            VerseId::new(book, chapter, verse),
+                            VerseContent::from(text.as_str()),
+                        )),
+                        None => introductions.push((book, text)),
+                    }
+                }
+                IndexedSource::new(VerseIndex::new(verses), vec![], introductions)
+            }
+        };
+        let source = source.with_metadata(conf.metadata());
+        Ok(Self { conf, source })
+    }
+}
+
+/// Reads the metadata of a SWORD module from its configuration file alone.
+pub(crate) fn read_module_metadata(conf_path: &Path) -> Result<SourceMetadata, SourceError> {
+    Ok(ModuleConf::read(conf_path)?.metadata())
+}
+
+/// Returns the configuration file of a SWORD module and the files in its data directory,
+/// which together make up the module.
+pub(crate) fn module_files(conf_path: &Path) -> Result<Vec<PathBuf>, SourceError> {
+    let data_path = ModuleConf::read(conf_path)?.data_path(sword_directory(conf_path)?)?;
+    let entries = fs::read_dir(&data_path).map_err(|error| SourceError::io(&data_path, error))?;
+    let mut paths = vec![conf_path.to_path_buf()];
+    for entry in entries {
+        let path = entry
+            .map_err(|error| SourceError::io(&data_path, error))?
+            .path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+/// Returns the SWORD directory of a configuration file in its `mods.d` directory.
+fn sword_directory(conf_path: &Path) -> Result<&Path, SourceError> {
+    conf_path.parent().and_then(Path::parent).ok_or_else(|| {
+        SourceError::UnexpectedStructure(format!("No SWORD directory for {}", conf_path.display()))
+    })
+}
+
+/// Configuration of a SWORD module, i.e. its name in brackets followed by `Key=Value` lines.
+struct ModuleConf {
+    name: String,
+    entries: Vec<(String, String)>,
+}
+impl ModuleConf {
+    fn read(path: &Path) -> Result<Self, SourceError> {
+        let data = fs::read(path).map_err(|error| SourceError::io(path, error))?;
+        Self::parse(&String::from_utf8_lossy(&data))
+            .map_err(|err| SourceError::UnexpectedStructure(format!("{}: {err}", path.display())))
+    }
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut entries: Vec<(String, String)> = vec![];
+        // Values ending with a backslash continue on the next line.
+        let mut is_continued = false;
+        for line in text.lines() {
+            let line = line.trim_end();
+            if is_continued {
+                if let Some((_, value)) = entries.last_mut() {
+                    value.push('\n');
+                    value.push_str(line.trim_end_matches('\\'));
+                }
+            } else if let Some(module_name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                name.get_or_insert_with(|| module_name.trim().to_string());
+            } else if let Some((key, value)) = line.split_once('=') {
+                entries.push((
+                    key.trim().to_string(),
+                    value.trim().trim_end_matches('\\').to_string(),
+                ));
+            }
+            is_continued = line.ends_with('\\');
+        }
+        Ok(Self {
+            name: name.ok_or("Expected a module name in brackets")?,
+            entries,
+        })
+    }
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+    fn data_path(&self, sword_path: &Path) -> Result<PathBuf, SourceError> {
+        let data_path = self.get("DataPath").ok_or_else(|| {
+            SourceError::UnexpectedStructure(format!("No data path for SWORD module {}", self.name))
+        })?;
+        Ok(sword_path.join(data_path.trim_start_matches("./")))
+    }
+    fn metadata(&self) -> SourceMetadata {
+        let value = |key: &str| self.get(key).map(str::to_string);
+        SourceMetadata {
+            title: value("Description"),
+            abbreviation: value("Abbreviation").or_else(|| Some(self.name.clone())),
+            language: value("Lang"),
+            publisher: None,
+            rights: value("Copyright").or_else(|| value("DistributionLicense")),
+            versification: value("Versification").or_else(|| Some("KJV".to_string())),
+            identifier: Some(self.name.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Driver {
+    /// Verses are stored uncompressed in `ot` and `nt`, indexed by `ot.vss` and `nt.vss`.
+    RawText { size_len: usize },
+    /// Verses are stored in compressed blocks in `ot.bzz` and `nt.bzz`, indexed by
+    /// blocks in `ot.bzs` and `nt.bzs` and verses within blocks in `ot.bzv` and `nt.bzv`.
+    ZText { size_len: usize },
+}
+
+/// Data files of a testament in a module.
+struct TestamentData {
+    driver: Driver,
+    /// Verse index, where each entry is the position and size of a verse.
+    index: Vec<u8>,
+    data: Vec<u8>,
+    /// Block index of compressed modules, where each entry is the position, size and
+    /// uncompressed size of a block.
+    blocks: Vec<u8>,
+    /// Latest decompressed block and its number. Verses are read in order,
+    /// so the same block is used for consecutive verses.
+    current_block: Option<(u32, Vec<u8>)>,
+}
+impl TestamentData {
+    /// Opens the data files of a testament, or returns `None` if the module has no such testament.
+    fn open(
+        data_path: &Path,
+        testament: Testament,
+        driver: Driver,
+    ) -> Result<Option<Self>, SourceError> {
+        let prefix = match testament {
+            Testament::Old => "ot",
+            Testament::New => "nt",
+        };
+        let (index_path, data_path, blocks_path) = match driver {
+            Driver::RawText { .. } => (
+                data_path.join(format!("{prefix}.vss")),
+                data_path.join(prefix),
+                None,
+            ),
+            Driver::ZText { .. } => (
+                data_path.join(format!("{prefix}.bzv")),
+                data_path.join(format!("{prefix}.bzz")),
+                Some(data_path.join(format!("{prefix}.bzs"))),
+            ),
+        };
+        if !index_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            driver,
+            index: read_file(&index_path)?,
+            data: read_file(&data_path)?,
+            blocks: match blocks_path {
+                Some(blocks_path) => read_file(&blocks_path)?,
+                None => vec![],
+            },
+            current_block: None,
+        }))
+    }
+    /// Returns the entry at the given index, or an empty entry if there's none.
+    fn entry(&mut self, index: usize) -> Result<Vec<u8>, SourceError> {
+        match self.driver {
+            Driver::RawText { size_len } => {
+                let position = index * (4 + size_len);
+                let Some((offset, size)) = read_entry(&self.index, position, size_len) else {
+                    return Ok(vec![]);
+                };
+                Ok(slice(&self.data, offset, size)?.to_vec())
+            }
+            Driver::ZText { size_len } => {
+                let position = index * (8 + size_len);
+                let (Some(block_number), Some((offset, size))) = (
+                    read_u32(&self.index, position),
+                    read_entry(&self.index, position + 4, size_len),
+                ) else {
+                    return Ok(vec![]);
+                };
+                if size == 0 {
+                    return Ok(vec![]);
+                }
+                let block = self.block(block_number)?;
+                Ok(slice(block, offset, size)?.to_vec())
+            }
+        }
+    }
+    fn block(&mut self, block_number: u32) -> Result<&[u8], SourceError> {
+        if !matches!(&self.current_block, Some((number, _)) if *number == block_number) {
+            let (offset, size) = read_entry(&self.blocks, block_number as usize * 12, 4)
+                .ok_or_else(|| {
+                    SourceError::UnexpectedStructure(format!(
+                        "Missing compressed block {block_number}"
+                    ))
+                })?;
+            let mut block = vec![];
+            ZlibDecoder::new(slice(&self.data, offset, size)?)
+                .read_to_end(&mut block)
+                .map_err(|err| {
+                    SourceError::UnexpectedStructure(format!(
+                        "Failed to decompress block {block_number}: {err}"
+                    ))
+                })?;
+            self.current_block = Some((block_number, block));
+        }
+        Ok(self
+            .current_block
+            .as_ref()
+            .map(|(_, block)| block.as_slice())
+            .unwrap_or_default())
+    }
+}
+
+/// Chapter and verse of an entry, or `None` if the entry is a book introduction.
+type ChapterVerse = Option<(u8, u8)>;
+
+/// Returns the books, chapters and verses of a testament with their indexes in the KJV
+/// versification, including book introductions.
+///
+/// The index starts with the module header and the testament introduction, and each book
+/// and chapter starts with its introduction, so Genesis 1:1 and Matthew 1:1 are at index 4.
+fn entry_indexes(testament: Testament) -> Vec<(Book, ChapterVerse, usize)> {
+    let mut entries = vec![];
+    let mut index = 2;
+    for book in testament.books() {
+        entries.push((book, None, index));
+        index += 1;
+        for chapter in 1..=book.chapter_count() {
+            // Chapter introductions are skipped.
+            index += 1;
+            for verse in 1..=book.verse_count(chapter).unwrap_or_default() {
+                entries.push((book, Some((chapter, verse)), index));
+                index += 1;
+            }
+        }
+    }
+    entries
+}
+/// Returns an OSIS document of verses and book introductions in OSIS markup.
+fn osis_document(entries: &[(Book, ChapterVerse, String)]) -> String {
+    let mut document = String::from("<osis><osisText>");
+    let mut current_book = None;
+    for (book, chapter_verse, text) in entries {
+        if current_book != Some(*book) {
+            if current_book.is_some() {
+                document.push_str("</div>");
+            }
+            document.push_str(&format!(r#"<div type="book" osisID="{}">"#, book.osis_id()));
+            current_book = Some(*book);
+        }
+        match chapter_verse {
+            Some((chapter, verse)) => document.push_str(&format!(
+                r#"<verse osisID="{}.{chapter}.{verse}">{text}</verse>"#,
+                book.osis_id()
+            )),
+            None => document.push_str(&format!(r#"<div type="introduction">{text}</div>"#)),
+        }
+    }
+    if current_book.is_some() {
+        document.push_str("</div>");
+    }
+    document.push_str("</osisText></osis>");
+    document
+}
+/// Returns the text of a verse in ThML, GBF or plain text without markup and notes,
+/// i.e. ThML `<note>` and GBF `<RF>` elements.
+fn strip_markup(text: &str) -> String {
+    let mut plain_text = String::new();
+    let mut is_within_note = false;
+    let mut rest = text;
+    while let Some(tag_start) = rest.find('<') {
+        if !is_within_note {
+            plain_text.push_str(&rest[..tag_start]);
+        }
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            rest = &rest[tag_start..];
+            break;
+        };
+        let tag = &rest[tag_start + 1..tag_start + tag_len];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "note" => is_within_note = !tag.starts_with('/') && !tag.ends_with('/'),
+            "RF" => is_within_note = true,
+            "Rf" => is_within_note = false,
+            _ => {}
+        }
+        rest = &rest[tag_start + tag_len + 1..];
+    }
+    if !is_within_note {
+        plain_text.push_str(rest);
+    }
+    let plain_text = plain_text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    collapse_whitespace(&plain_text)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, SourceError> {
+    fs::read(path).map_err(|error| SourceError::io(path, error))
+}
+fn read_u32(bytes: &[u8], position: usize) -> Option<u32> {
+    let bytes = bytes.get(position..position + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+/// Reads an index entry of a 4-byte position followed by a 2-byte or 4-byte size.
+fn read_entry(bytes: &[u8], position: usize, size_len: usize) -> Option<(u32, u32)> {
+    let offset = read_u32(bytes, position)?;
+    let size = match size_len {
+        2 => u16::from_le_bytes(bytes.get(position + 4..position + 6)?.try_into().ok()?) as u32,
+        _ => read_u32(bytes, position + 4)?,
+    };
+    Some((offset, size))
+}
+fn slice(bytes: &[u8], offset: u32, size: u32) -> Result<&[u8], SourceError> {
+    let start = offset as usize;
+    bytes.get(start..start + size as usize).ok_or_else(|| {
+        SourceError::UnexpectedStructure(format!(
+            "Entry at {offset} with size {size} exceeds the data"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, path::PathBuf};
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+    use crate::{parse_reference, StrongsNumber};
+
+    fn sword_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "raqiya-bible-reference-sword-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(directory.join("mods.d")).unwrap();
+        directory
+    }
+    /// Returns index entries of verses at the given indexes, each a position and a 2-byte size
+    /// with the given number of leading bytes, along with their concatenated data.
+    fn index_entries(entries: &[(usize, &[u8])], leading: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let entry_len = leading.len() + 6;
+        let last_index = entries.iter().map(|(index, _)| *index).max().unwrap();
+        let mut index = vec![0; (last_index + 1) * entry_len];
+        let mut data = vec![];
+        for (entry_index, text) in entries {
+            let position = entry_index * entry_len;
+            index[position..position + leading.len()].copy_from_slice(leading);
+            let position = position + leading.len();
+            index[position..position + 4].copy_from_slice(&(data.len() as u32).to_le_bytes());
+            index[position + 4..position + 6].copy_from_slice(&(text.len() as u16).to_le_bytes());
+            data.extend_from_slice(text);
+        }
+        (index, data)
+    }
+    /// Returns the path of the module configuration of the given module in the given SWORD directory,
+    /// following the convention of lowercase file names.
+    fn conf_path(sword_path: &Path, module_name: &str) -> PathBuf {
+        sword_path
+            .join("mods.d")
+            .join(format!("{}.conf", module_name.to_lowercase()))
+    }
+    fn plain_texts(source: &SwordSource, reference: &str) -> Vec<String> {
+        source
+            .find_content(&parse_reference(reference).unwrap())
+            .unwrap()
+            .iter()
+            .map(|reference| reference.content.plain_text())
+            .collect()
+    }
+
+    #[test]
+    fn read_raw_text_module_in_osis_markup() {
+        let sword_path = sword_directory("raw");
+        fs::write(
+            conf_path(&sword_path, "KJV"),
+            "[KJV]\nDataPath=./modules/texts/rawtext/kjv/\nModDrv=RawText\n\
+             SourceType=OSIS\nEncoding=UTF-8\nDescription=King James Version (1769)\\\n with Strongs Numbers\n",
+        )
+        .unwrap();
+        let (index, data) = index_entries(
+            &[
+                (2, b"<p>The First Book of Moses, called Genesis.</p>"),
+                (4, br#"<title>The creation</title>In the beginning <w lemma="strong:H0430">God</w> created the heaven and the earth."#),
+                (5, b"And the earth was without form, and void.\n"),
+                (36, b"Thus the heavens and the earth were finished, and all the host of them."),
+            ],
+            &[],
+        );
+        let data_path = sword_path.join("modules/texts/rawtext/kjv");
+        fs::create_dir_all(&data_path).unwrap();
+        fs::write(data_path.join("ot.vss"), index).unwrap();
+        fs::write(data_path.join("ot"), data).unwrap();
+
+        fs::write(
+            sword_path.join("mods.d/broken.conf"),
+            "DataPath=./modules/texts/rawtext/broken/\n",
+        )
+        .unwrap();
+        let source = SwordSource::open(&sword_path, "kjv").unwrap();
+        assert!(SwordSource::open(&sword_path, "ASV").is_err());
+        assert_eq!(source.module_name(), "KJV");
+        assert_eq!(
+            source.conf_value("description"),
+            Some("King James Version (1769)\n with Strongs Numbers")
+        );
+        assert_eq!(source.metadata().abbreviation.as_deref(), Some("KJV"));
+        assert_eq!(source.metadata().versification.as_deref(), Some("KJV"));
+        assert_eq!(
+            plain_texts(&source, "Genesis 1:1-2"),
+            [
+                "In the beginning God created the heaven and the earth.",
+                "And the earth was without form, and void."
+            ]
+        );
+        assert_eq!(
+            plain_texts(&source, "Genesis 2:1"),
+            ["Thus the heavens and the earth were finished, and all the host of them."]
+        );
+        assert_eq!(
+            source
+                .as_index()
+                .find_verses_with_strongs(StrongsNumber::Hebrew(430))
+                .len(),
+            1
+        );
+        let parse_result = parse_reference("Genesis 1").unwrap();
+        assert_eq!(
+            source.as_index().find_headings(&parse_result)[0].text,
+            "The creation"
+        );
+        assert_eq!(
+            source.as_index().find_introduction(Book::Genesis),
+            Some("The First Book of Moses, called Genesis.")
+        );
+    }
+    #[test]
+    fn read_compressed_module_in_latin_1() {
+        let sword_path = sword_directory("compressed");
+        fs::write(
+            conf_path(&sword_path, "FinPR"),
+            "[FinPR]\nDataPath=./modules/texts/ztext/finpr/\nModDrv=zText\nSourceType=ThML\n",
+        )
+        .unwrap();
+        // Verses in a single block, so the index of each verse starts with the block number 0.
+        let (index, block) = index_entries(
+            &[
+                (4, b"Jeesuksen Kristuksen, Daavidin pojan, Aabrahamin pojan, syntykirja.<note>Luuk. 3:23</note>"),
+                (5, b"Aabrahamille syntyi Iisak, Iisakille syntyi Jaakob, Jaakobille syntyi Juuda ja h\xe4nen veljens\xe4;"),
+            ],
+            &0u32.to_le_bytes(),
+        );
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(&block).unwrap();
+        let data = encoder.finish().unwrap();
+        let mut blocks = vec![];
+        for value in [0, data.len() as u32, block.len() as u32] {
+            blocks.extend_from_slice(&value.to_le_bytes());
+        }
+        let data_path = sword_path.join("modules/texts/ztext/finpr");
+        fs::create_dir_all(&data_path).unwrap();
+        fs::write(data_path.join("nt.bzv"), index).unwrap();
+        fs::write(data_path.join("nt.bzs"), blocks).unwrap();
+        fs::write(data_path.join("nt.bzz"), data).unwrap();
+
+        let source = SwordSource::from_conf_file(&conf_path(&sword_path, "FinPR")).unwrap();
+        assert_eq!(
+            plain_texts(&source, "Matthew 1:1-2"),
+            [
+                "Jeesuksen Kristuksen, Daavidin pojan, Aabrahamin pojan, syntykirja.",
+                "Aabrahamille syntyi Iisak, Iisakille syntyi Jaakob, Jaakobille syntyi Juuda ja hänen veljensä;"
+            ]
+        );
+        assert!(plain_texts(&source, "Genesis 1:1").is_empty());
+
+        fs::write(
+            conf_path(&sword_path, "FinPR"),
+            "[FinPR]\nDataPath=./modules/texts/ztext/finpr/\nModDrv=zText\nVersification=Luther\n",
+        )
+        .unwrap();
+        assert_eq!(
+            SwordSource::open(&sword_path, "FinPR")
+                .err()
+                .unwrap()
+                .to_string(),
+            "Unsupported source: Versification of SWORD module FinPR: Luther"
+        );
+    }
+}
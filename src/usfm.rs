@@ -0,0 +1,773 @@
+use std::{fs, path::Path};
+
+use crate::{
+    osis::{collapse_whitespace, parse_morphology_codes, PendingHeading},
+    parse_reference, Book, Break, Heading, HeadingType, IndexedSource, Note, NoteType, Reference,
+    ReferenceParseResult, Segment, Source, SourceError, StrongsNumber, TextStyle, VerseContent,
+    VerseId, VerseIndex, Word,
+};
+
+/// Represents a source of Bible content in Unified Standard Format Markers (USFM),
+/// where each book is a separate file starting with its book code, e.g. `\id GEN`.
+///
+/// Chapters (`\c`) and verses (`\v`) are read into the same content as from an equivalent
+/// [`crate::OsisSource`]:
+/// - Character markers such as `\add`, `\nd`, `\it`, `\qt` and `\wj` are read as text styles.
+///   Quotation milestones (`\qt-s |who="Jesus"\*` ... `\qt-e\*`) may cross verses.
+/// - Words tagged with `\w grace|strong="H2580" x-morph="strongMorph:TH8804"\w*` are read as
+///   [`Word`] segments.
+/// - Footnotes (`\f`) and cross-references (`\x`) are read as [`Note`] segments.
+/// - Paragraphs (`\p`), stanza breaks (`\b`) and poetic lines (`\q1`, `\q2`) are read as
+///   [`Break`] segments.
+/// - Headings (`\mt`, `\ms`, `\s`, `\d`, `\qa`) and introductions (`\ip`, `\is` etc.)
+///   are read separately, like in OSIS.
+/// - Combined verses (`\v 1-2`) have the same content under each of their verse numbers.
+///
+/// The whole source is parsed when created. Book files of books outside the canon of 66 books,
+/// e.g. front matter or deuterocanonical books, are skipped.
+///
+/// See https://ubsicap.github.io/usfm/ for more information.
+pub struct UsfmSource {
+    source: IndexedSource,
+}
+impl Source for UsfmSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+}
+impl UsfmSource {
+    /// Reads the book files with the extension `.usfm` or `.sfm` in a directory.
+    pub fn from_directory(path: &Path) -> Result<Self, SourceError> {
+        let entries = fs::read_dir(path).map_err(|error| SourceError::io(path, error))?;
+        let mut book_paths = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|error| SourceError::io(path, error))?;
+            let book_path = entry.path();
+            let is_usfm = book_path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    extension.eq_ignore_ascii_case("usfm") || extension.eq_ignore_ascii_case("sfm")
+                });
+            if is_usfm {
+                book_paths.push(book_path);
+            }
+        }
+        // Sort for a deterministic result when a book is in many files.
+        book_paths.sort();
+
+        let books = book_paths
+            .iter()
+            .map(|book_path| {
+                fs::read_to_string(book_path).map_err(|error| SourceError::io(book_path, error))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_books(books)
+    }
+    /// Creates a source from the USFM content of book files already in memory.
+    pub fn from_books(
+        books: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Self, SourceError> {
+        let books = books
+            .into_iter()
+            .map(|book| {
+                let mut reader = BookReader::default();
+                for token in tokenize(book.as_ref()) {
+                    reader.handle_token(token);
+                }
+                reader.finish()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            source: index_books(books),
+        })
+    }
+
+    /// Returns the parsed source, e.g. to find headings, Psalm titles and introductions.
+    pub fn as_index(&self) -> &IndexedSource {
+        &self.source
+    }
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+}
+
+/// Marker or text of USFM content.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Token<'a> {
+    /// Marker without the backslash, e.g. "v" of `\v` or "nd" of `\nd*`.
+    /// The plus sign of nested character markers (`\+nd`) is left out,
+    /// and the name of the end of a milestone (`\*`) is empty.
+    Marker {
+        name: &'a str,
+        is_closing: bool,
+    },
+    Text(&'a str),
+}
+
+/// Splits USFM content into markers and text. The whitespace that separates
+/// an opening marker from the text after it is not part of the text.
+fn tokenize(usfm: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = usfm;
+    while !rest.is_empty() {
+        let Some(marker) = rest.strip_prefix('\\') else {
+            let text_len = rest.find('\\').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..text_len]));
+            rest = &rest[text_len..];
+            continue;
+        };
+        let name_len = marker
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '-'))
+            .unwrap_or(marker.len());
+        let name = marker[..name_len].trim_start_matches('+');
+        rest = &marker[name_len..];
+        let is_closing = rest.starts_with('*');
+        if is_closing {
+            rest = &rest[1..];
+        } else if let Some(separator) = rest.chars().next().filter(|c| c.is_whitespace()) {
+            rest = &rest[separator.len_utf8()..];
+        }
+        tokens.push(Token::Marker { name, is_closing });
+    }
+    tokens
+}
+
+/// Kind of paragraph started by a paragraph marker.
+enum Paragraph {
+    /// Paragraph of verse text, possibly starting with a break.
+    Body(Option<Break>),
+    Heading(HeadingType, bool),
+    Introduction,
+    /// Paragraph of content that is not read, e.g. running headers.
+    Skip,
+}
+impl Paragraph {
+    /// Returns the kind of paragraph of a marker, or `None` if it's not a paragraph marker.
+    fn of_marker(name: &str) -> Option<Self> {
+        // Many markers are numbered by level, e.g. `\q2` or `\s1`.
+        let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+        let level = name[base.len()..].parse::<u8>().unwrap_or(1);
+        let paragraph = match base {
+            "p" | "m" | "po" | "pr" | "cls" | "pmo" | "pm" | "pmc" | "pmr" | "pi" | "mi" | "pc"
+            | "ph" | "li" | "lh" | "lf" | "lim" | "qd" | "tr" => Self::Body(Some(Break::Paragraph)),
+            "nb" => Self::Body(None),
+            "q" | "qr" | "qc" | "qm" => Self::Body(Some(Break::Line {
+                indent: level.saturating_sub(1),
+            })),
+            "b" => Self::Body(Some(Break::Stanza)),
+            "mt" => Self::Heading(HeadingType::Book, false),
+            "ms" => Self::Heading(HeadingType::Other("majorSection".to_string()), false),
+            "s" => Self::Heading(HeadingType::Section, false),
+            "d" => Self::Heading(HeadingType::Psalm, true),
+            "qa" => Self::Heading(HeadingType::Acrostic, false),
+            "imt" | "imte" | "is" | "ip" | "ipi" | "im" | "imi" | "ipq" | "imq" | "ipr" | "iq"
+            | "ib" | "ili" | "iot" | "io" | "iex" => Self::Introduction,
+            "id" | "ide" | "h" | "toc" | "toca" | "rem" | "sts" | "usfm" | "cl" | "cp" | "cd"
+            | "r" | "sr" | "mr" | "sp" | "mte" | "ie" => Self::Skip,
+            _ => return None,
+        };
+        Some(paragraph)
+    }
+}
+
+/// Paragraph currently being read.
+enum Block {
+    Body,
+    Heading(PendingHeading),
+    Introduction,
+    Skip,
+}
+
+/// Number expected as the first word of the next text.
+#[derive(Clone, Copy)]
+enum ExpectedNumber {
+    BookCode,
+    Chapter,
+    Verse,
+}
+
+/// Part of a note whose text is currently being read.
+#[derive(Clone, Copy, PartialEq)]
+enum NoteField {
+    /// Caller of the note, e.g. "+", which comes first.
+    Caller,
+    /// Text of the note, e.g. of `\ft`.
+    Text,
+    /// Cross-reference targets (`\xt`), which are part of the text.
+    Targets,
+    /// Reference of the verse the note is attached to (`\fr`, `\xo`), which is not read.
+    Origin,
+}
+
+/// Verses read from a book, along with their headings and the introduction of the book.
+pub(crate) struct BookContent {
+    verses: Vec<(VerseId, VerseContent)>,
+    headings: Vec<Heading>,
+    introductions: Vec<(Book, String)>,
+}
+
+/// Reads the content of a book from USFM tokens.
+///
+/// Other formats with the same markers, like USX, are read by converting them into tokens.
+#[derive(Default)]
+pub(crate) struct BookReader {
+    /// Whether the book code of the `\id` marker has been read.
+    has_book_code: bool,
+    /// Book of the book code, or `None` if the book is not supported.
+    book: Option<Book>,
+    chapter: u8,
+    expected_number: Option<ExpectedNumber>,
+    block: Option<Block>,
+    verses: Vec<(VerseId, VerseContent)>,
+    /// Verse currently being read and its content so far.
+    current_verse: Option<(VerseId, VerseContent)>,
+    /// Further verse IDs of the verse currently being read, if it combines verses, e.g. `\v 1-2`.
+    combined_verse_ids: Vec<VerseId>,
+    /// Breaks read after the latest text. They come at the start of the next verse,
+    /// unless more text of the current verse follows.
+    pending_breaks: Vec<Break>,
+    headings: Vec<Heading>,
+    /// Headings read after the latest verse started, which stand before the next verse.
+    pending_headings: Vec<PendingHeading>,
+    /// Introduction text read so far.
+    introduction: String,
+    introductions: Vec<(Book, String)>,
+    /// Open character markers, each with the text style within it, the innermost last.
+    style_stack: Vec<(String, TextStyle)>,
+    /// Open quotation milestones, each with whether it contains words of Christ.
+    open_quotation_milestones: Vec<bool>,
+    /// Milestone marker currently being read and its attributes so far.
+    current_milestone: Option<(String, String)>,
+    /// Character marker whose content is skipped until it closes, e.g. `\va` of alternate
+    /// verse numbers.
+    skipped_marker: Option<String>,
+    /// Note currently being read, the part of it being read and its cross-reference targets.
+    current_note: Option<(Note, NoteField, String)>,
+    /// Tagged word currently being read, including its attributes after `|`.
+    current_word: Option<(String, TextStyle)>,
+}
+impl BookReader {
+    pub(crate) fn handle_token(&mut self, token: Token) {
+        if self.has_book_code && self.book.is_none() {
+            return;
+        }
+        match token {
+            Token::Marker { name, is_closing } => self.handle_marker(name, is_closing),
+            Token::Text(text) => self.handle_text(text),
+        }
+    }
+    fn handle_marker(&mut self, name: &str, is_closing: bool) {
+        if self.current_milestone.is_some() {
+            if name.is_empty() && is_closing {
+                self.finish_milestone();
+            }
+            return;
+        }
+        if let Some(skipped_marker) = &self.skipped_marker {
+            if is_closing && name == skipped_marker {
+                self.skipped_marker = None;
+            }
+            return;
+        }
+        if let Some((_, field, _)) = &mut self.current_note {
+            match name {
+                "f" | "fe" | "ef" | "x" | "ex" if is_closing => self.finish_note(),
+                _ if is_closing => *field = NoteField::Text,
+                "fr" | "xo" => *field = NoteField::Origin,
+                "xt" => *field = NoteField::Targets,
+                _ => *field = NoteField::Text,
+            }
+            return;
+        }
+
+        match name {
+            "id" => {
+                self.start_paragraph(Paragraph::Skip);
+                self.expected_number = Some(ExpectedNumber::BookCode);
+            }
+            "c" => {
+                self.finish_verse();
+                self.finish_introduction();
+                self.start_paragraph(Paragraph::Body(None));
+                self.expected_number = Some(ExpectedNumber::Chapter);
+            }
+            "v" => {
+                if !matches!(self.block, Some(Block::Body)) {
+                    self.start_paragraph(Paragraph::Body(None));
+                }
+                self.expected_number = Some(ExpectedNumber::Verse);
+            }
+            _ if name.ends_with("-s") || name.ends_with("-e") => {
+                self.current_milestone = Some((name.to_string(), String::new()));
+            }
+            "w" if is_closing => self.finish_word(),
+            "w" => self.current_word = Some((String::new(), self.current_style())),
+            "f" | "fe" | "ef" | "x" | "ex" if !is_closing => {
+                let note_type = match name {
+                    "x" | "ex" => Some(NoteType::CrossReference),
+                    "ef" => Some(NoteType::Study),
+                    _ => None,
+                };
+                let note = Note {
+                    note_type,
+                    text: String::new(),
+                    references: Vec::new(),
+                };
+                self.current_note = Some((note, NoteField::Caller, String::new()));
+            }
+            "ca" | "va" | "vp" | "rq" | "fig" | "cat" if !is_closing => {
+                self.skipped_marker = Some(name.to_string());
+            }
+            _ if is_closing => {
+                if let Some(index) = self
+                    .style_stack
+                    .iter()
+                    .rposition(|(open_name, _)| open_name == name)
+                {
+                    self.style_stack.truncate(index);
+                }
+            }
+            _ => match Paragraph::of_marker(name) {
+                Some(paragraph) => self.start_paragraph(paragraph),
+                // Unknown character markers keep the style of the surrounding text.
+                None => {
+                    let style = character_style(self.current_style(), name);
+                    self.style_stack.push((name.to_string(), style));
+                }
+            },
+        }
+    }
+    fn handle_text(&mut self, mut text: &str) {
+        if let Some((_, attributes)) = &mut self.current_milestone {
+            attributes.push_str(text);
+            return;
+        }
+        if self.skipped_marker.is_some() {
+            return;
+        }
+        if let Some(expected_number) = self.expected_number {
+            let trimmed = text.trim_start();
+            if trimmed.is_empty() {
+                return;
+            }
+            self.expected_number = None;
+            let word_len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let word = &trimmed[..word_len];
+            text = &trimmed[word_len..];
+            match expected_number {
+                ExpectedNumber::BookCode => {
+                    self.has_book_code = true;
+                    self.book = Book::from_usfm_id(word);
+                }
+                ExpectedNumber::Chapter => self.chapter = parse_number(word).unwrap_or(0),
+                ExpectedNumber::Verse => {
+                    let (number_from, number_to) = parse_verse_span(word).unwrap_or((0, 0));
+                    self.start_verse(number_from, number_to);
+                }
+            }
+        }
+        if let Some((note, field, targets)) = &mut self.current_note {
+            match field {
+                NoteField::Caller => {
+                    // The caller is a single character or word followed by the note text.
+                    let text = text.trim_start();
+                    let caller_len = text.find(char::is_whitespace).unwrap_or(text.len());
+                    note.text.push_str(&text[caller_len..]);
+                    if !text.is_empty() {
+                        *field = NoteField::Text;
+                    }
+                }
+                NoteField::Text => note.text.push_str(text),
+                NoteField::Targets => {
+                    note.text.push_str(text);
+                    targets.push_str(text);
+                }
+                NoteField::Origin => {}
+            }
+            return;
+        }
+        if let Some((word, _)) = &mut self.current_word {
+            word.push_str(text);
+            return;
+        }
+        match &mut self.block {
+            Some(Block::Heading(heading)) => heading.text.push_str(text),
+            Some(Block::Introduction) => self.introduction.push_str(text),
+            Some(Block::Body) => {
+                let style = self.current_style();
+                // Whitespace alone doesn't continue a verse after a break.
+                let content = if text.trim().is_empty() {
+                    self.current_verse.as_mut().map(|(_, content)| content)
+                } else {
+                    self.current_content()
+                };
+                if let Some(content) = content {
+                    content.push_text(text, style);
+                }
+            }
+            Some(Block::Skip) | None => {}
+        }
+    }
+
+    /// Finishes the current paragraph without starting a new one, so that text up to the next
+    /// paragraph is not read.
+    pub(crate) fn end_paragraph(&mut self) {
+        self.start_paragraph(Paragraph::Skip);
+    }
+    /// Finishes the current paragraph and starts a new one.
+    fn start_paragraph(&mut self, paragraph: Paragraph) {
+        if let Some(Block::Heading(mut heading)) = self.block.take() {
+            heading.text = collapse_whitespace(&heading.text);
+            if !heading.text.is_empty() {
+                self.pending_headings.push(heading);
+            }
+        }
+        // Character markers end at the end of a paragraph.
+        self.style_stack.clear();
+        self.block = Some(match paragraph {
+            Paragraph::Body(layout_break) => {
+                if let Some(layout_break) = layout_break {
+                    self.pending_breaks.push(layout_break);
+                }
+                Block::Body
+            }
+            Paragraph::Heading(heading_type, canonical) => Block::Heading(PendingHeading {
+                heading_type,
+                canonical,
+                text: String::new(),
+            }),
+            Paragraph::Introduction => {
+                self.introduction.push(' ');
+                Block::Introduction
+            }
+            Paragraph::Skip => Block::Skip,
+        });
+    }
+    fn start_verse(&mut self, number_from: u8, number_to: u8) {
+        self.finish_verse();
+        let Some(book) = self.book.filter(|_| self.chapter > 0 && number_from > 0) else {
+            return;
+        };
+        let id = VerseId::new(book, self.chapter, number_from);
+        self.combined_verse_ids = (number_from + 1..=number_to)
+            .map(|number| VerseId::new(book, self.chapter, number))
+            .collect();
+        self.headings.extend(
+            std::mem::take(&mut self.pending_headings)
+                .into_iter()
+                .map(|heading| heading.into_heading(id)),
+        );
+        self.current_verse = Some((id, VerseContent::default()));
+    }
+    /// Returns the content of the verse currently being read,
+    /// after adding the breaks that turned out to be within the verse.
+    fn current_content(&mut self) -> Option<&mut VerseContent> {
+        let (_, content) = self.current_verse.as_mut()?;
+        for layout_break in self.pending_breaks.drain(..) {
+            content.push(Segment::Break(layout_break));
+            // Separate text on different lines and paragraphs also in plain text.
+            content.push_text(" ", TextStyle::default());
+        }
+        Some(content)
+    }
+    /// Returns the style of text at the current position.
+    fn current_style(&self) -> TextStyle {
+        let mut style = self
+            .style_stack
+            .last()
+            .map(|(_, style)| *style)
+            .unwrap_or_default();
+        style.quotation |= !self.open_quotation_milestones.is_empty();
+        style.words_of_christ |= self.open_quotation_milestones.contains(&true);
+        style
+    }
+    fn finish_milestone(&mut self) {
+        let Some((name, attributes)) = self.current_milestone.take() else {
+            return;
+        };
+        let base = name[..name.len() - 2].trim_end_matches(|c: char| c.is_ascii_digit());
+        if base != "qt" {
+            return;
+        }
+        if name.ends_with("-s") {
+            let attributes = attributes.trim_start().trim_start_matches('|');
+            let is_words_of_christ = parse_attributes(attributes, "who")
+                .iter()
+                .any(|(key, value)| *key == "who" && *value == "Jesus");
+            self.open_quotation_milestones.push(is_words_of_christ);
+        } else {
+            self.open_quotation_milestones.pop();
+        }
+    }
+    fn finish_word(&mut self) {
+        let Some((text, style)) = self.current_word.take() else {
+            return;
+        };
+        let (text, attributes) = text.split_once('|').unwrap_or((&text, ""));
+        let mut word = Word {
+            text: collapse_whitespace(text),
+            style,
+            strongs: Vec::new(),
+            morphology: Vec::new(),
+        };
+        for (key, value) in parse_attributes(attributes, "lemma") {
+            match key {
+                "strong" => word.strongs.extend(
+                    value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        // Strong's numbers may have a prefix, e.g. "b:H7225".
+                        .filter_map(|strongs| strongs.rsplit(':').next())
+                        .filter_map(StrongsNumber::parse),
+                ),
+                "x-morph" => word.morphology.extend(parse_morphology_codes(value)),
+                _ => {}
+            }
+        }
+        if matches!(self.block, Some(Block::Body)) {
+            if let Some(content) = self.current_content() {
+                content.push(Segment::Word(word));
+            }
+        }
+    }
+    fn finish_note(&mut self) {
+        let Some((mut note, _, targets)) = self.current_note.take() else {
+            return;
+        };
+        note.text = collapse_whitespace(&note.text);
+        note.references.extend(
+            targets
+                .split(';')
+                .filter_map(|target| parse_reference(target.trim()).ok())
+                .filter_map(|parse_result| parse_result.verse_id_range()),
+        );
+        if matches!(self.block, Some(Block::Body)) {
+            if let Some(content) = self.current_content() {
+                content.push(Segment::Note(note));
+            }
+        }
+    }
+    /// Adds the verse currently being read, if any, to the verses read.
+    pub(crate) fn finish_verse(&mut self) {
+        if let Some((id, mut content)) = self.current_verse.take() {
+            content.collapse_whitespace();
+            let ids = std::iter::once(id).chain(std::mem::take(&mut self.combined_verse_ids));
+            self.verses.extend(ids.map(|id| (id, content.clone())));
+        }
+    }
+    fn finish_introduction(&mut self) {
+        let introduction = collapse_whitespace(&std::mem::take(&mut self.introduction));
+        if let (Some(book), false) = (self.book, introduction.is_empty()) {
+            self.introductions.push((book, introduction));
+        }
+    }
+    pub(crate) fn has_book_code(&self) -> bool {
+        self.has_book_code
+    }
+    pub(crate) fn finish(mut self) -> Result<BookContent, SourceError> {
+        if !self.has_book_code {
+            return Err(SourceError::UnexpectedStructure(
+                "USFM book has no book code (\\id)".to_string(),
+            ));
+        }
+        self.end_paragraph();
+        self.finish_verse();
+        self.finish_introduction();
+        Ok(BookContent {
+            verses: self.verses,
+            headings: self.headings,
+            introductions: self.introductions,
+        })
+    }
+}
+
+/// Creates an indexed source of the content of books.
+pub(crate) fn index_books(books: Vec<BookContent>) -> IndexedSource {
+    let mut verses = Vec::new();
+    let mut headings = Vec::new();
+    let mut introductions = Vec::new();
+    for book in books {
+        verses.extend(book.verses);
+        headings.extend(book.headings);
+        introductions.extend(book.introductions);
+    }
+    IndexedSource::new(VerseIndex::new(verses), headings, introductions)
+}
+/// Returns the text style within a character marker, given the style of the surrounding text.
+fn character_style(parent_style: TextStyle, name: &str) -> TextStyle {
+    let mut style = parent_style;
+    match name {
+        "add" => style.added = true,
+        "nd" => style.divine_name = true,
+        "it" | "bdit" => style.italic = true,
+        "qt" => style.quotation = true,
+        "wj" => style.words_of_christ = true,
+        _ => {}
+    }
+    style
+}
+/// Parses attributes of the form `strong="H2580" x-morph="..."` into keys and values.
+/// A single value without a key is the value of the default attribute.
+fn parse_attributes<'a>(attributes: &'a str, default_key: &'a str) -> Vec<(&'a str, &'a str)> {
+    let attributes = attributes.trim();
+    if attributes.is_empty() {
+        return vec![];
+    }
+    if !attributes.contains('=') {
+        return vec![(default_key, attributes)];
+    }
+    let mut parsed = Vec::new();
+    let mut rest = attributes;
+    while let Some((key, value)) = rest.split_once('=') {
+        let Some(value) = value.trim_start().strip_prefix('"') else {
+            break;
+        };
+        let Some((value, next)) = value.split_once('"') else {
+            break;
+        };
+        parsed.push((key.trim(), value));
+        rest = next;
+    }
+    parsed
+}
+/// Parses a verse number or a span of combined verses into the first and the last verse number,
+/// e.g. (1, 2) of "1-2" or (1, 1) of "1a" for a part of a verse.
+pub(crate) fn parse_verse_span(value: &str) -> Option<(u8, u8)> {
+    let (number_from, number_to) = value.split_once('-').unwrap_or((value, value));
+    let number_from = parse_number(number_from)?;
+    let number_to = parse_number(number_to)
+        .filter(|number_to| *number_to >= number_from)
+        .unwrap_or(number_from);
+    Some((number_from, number_to))
+}
+/// Parses the leading digits of a chapter or verse number,
+/// e.g. 1 of "1a" for a part of a verse.
+pub(crate) fn parse_number(value: &str) -> Option<u8> {
+    let digits_len = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..digits_len].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OsisSource;
+
+    const JOHN_USFM: &str = r#"\id JHN King James Version
+\h John
+\mt1 The Gospel according to John
+\ip The Gospel according to \bk John\bk*.
+\c 3
+\s1 The new birth
+\p
+\v 3 Jesus answered and said unto him, \qt-s |who="Jesus"\*Verily, verily, I say unto thee, Except a man be born again\f + \fr 3:3 \ft Or, from above\f*, he cannot see the kingdom of God.\qt-e\*
+\p
+\v 16 \qt-s |who="Jesus"\*For \w God|strong="G2316" x-morph="robinson:N-NSM"\w* so loved the world\x - \xo 3:16 \xt Rom. 5:8\x*, that he gave his only begotten Son, that whosoever believeth in him should not perish, but have everlasting life.\qt-e\*
+"#;
+    const PSALMS_USFM: &str = r#"\id PSA
+\c 23
+\d A Psalm of David.
+\q1
+\v 1 The \nd LORD\nd* \add is\add* my shepherd;
+\q2 I shall not want.
+\q1
+\v 2 He maketh me to lie down in green pastures:
+"#;
+    const EQUIVALENT_OSIS: &str = r#"<osis><osisText>
+        <div type="book" osisID="Ps">
+          <chapter osisID="Ps.23">
+            <title type="psalm" canonical="true">A Psalm of David.</title>
+            <l><verse osisID="Ps.23.1" sID="Ps.23.1"/>The <divineName>LORD</divineName> <transChange type="added">is</transChange> my shepherd;</l>
+            <l level="2">I shall not want.<verse eID="Ps.23.1"/></l>
+            <l><verse osisID="Ps.23.2" sID="Ps.23.2"/>He maketh me to lie down in green pastures:<verse eID="Ps.23.2"/></l>
+          </chapter>
+        </div>
+        <div type="book" osisID="John">
+          <title type="main">The Gospel according to John</title>
+          <div type="introduction"><p>The Gospel according to John.</p></div>
+          <chapter osisID="John.3">
+            <title>The new birth</title>
+            <p><verse osisID="John.3.3">Jesus answered and said unto him, <q who="Jesus">Verily, verily, I say unto thee, Except a man be born again<note>Or, from above</note>, he cannot see the kingdom of God.</q></verse></p>
+            <p><verse osisID="John.3.16"><q who="Jesus">For <w lemma="strong:G2316" morph="robinson:N-NSM">God</w> so loved the world<note type="crossReference"><reference osisRef="Rom.5.8">Rom. 5:8</reference></note>, that he gave his only begotten Son, that whosoever believeth in him should not perish, but have everlasting life.</q></verse></p>
+          </chapter>
+        </div>
+        </osisText></osis>"#;
+
+    #[test]
+    fn find_same_content_as_equivalent_osis() {
+        let usfm_source = UsfmSource::from_books([PSALMS_USFM, JOHN_USFM]).unwrap();
+        let osis_source = OsisSource::from_data(EQUIVALENT_OSIS.into());
+        for reference in ["John 3", "John 3:16", "Psalms 23", "Psalms 23:1-2"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                usfm_source.find_content(&parse_result).unwrap(),
+                osis_source.find_content(&parse_result).unwrap(),
+                "{reference}"
+            );
+            assert_eq!(
+                usfm_source.as_index().find_headings(&parse_result),
+                osis_source.find_headings(&parse_result).unwrap(),
+                "{reference}"
+            );
+        }
+        assert_eq!(
+            usfm_source.as_index().find_introduction(Book::John),
+            Some("The Gospel according to John.")
+        );
+        assert_eq!(
+            usfm_source.as_index().find_psalm_title(23),
+            Some("A Psalm of David.")
+        );
+        assert_eq!(
+            usfm_source
+                .as_index()
+                .find_verses_with_strongs(StrongsNumber::Greek(2316))
+                .len(),
+            1
+        );
+    }
+    #[test]
+    fn read_character_markers_combined_verses_and_unsupported_books() {
+        let usfm_source = UsfmSource::from_books([
+            "\\id FRT Front matter\n\\c 1\n\\p\n\\v 1 Preface",
+            "\\id MAT\n\\c 5\n\\p\n\\v 1-2 And he \\+add opened\\+add* his mouth.\n\\v 3 \\wj Blessed \\+w are|G3107\\+w* the poor in spirit\\wj*",
+        ])
+        .unwrap();
+        let references = usfm_source
+            .find_content(&parse_reference("Matthew 5:1+").unwrap())
+            .unwrap();
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| (reference.number, reference.content.plain_text()))
+                .collect::<Vec<_>>(),
+            [
+                (1, "And he opened his mouth.".to_string()),
+                (2, "And he opened his mouth.".to_string()),
+                (3, "Blessed are the poor in spirit".to_string())
+            ]
+        );
+        assert_eq!(
+            references[2].content.words_of_christ(),
+            ["Blessed are the poor in spirit"]
+        );
+        // A value without an attribute name is a lemma, not a Strong's number.
+        assert!(references[2]
+            .content
+            .words()
+            .all(|word| word.strongs.is_empty()));
+
+        let error = UsfmSource::from_books(["\\c 1\n\\v 1 In the beginning"])
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected structure: USFM book has no book code (\\id)"
+        );
+    }
+}
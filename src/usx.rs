@@ -0,0 +1,316 @@
+use std::{fs, io::Cursor, path::Path};
+
+use xml::{attribute::OwnedAttribute, reader::XmlEvent};
+
+use crate::{
+    osis::find_xml_attribute,
+    usfm::{index_books, BookContent, BookReader, Token},
+    IndexedSource, Reference, ReferenceParseResult, Source, SourceError,
+};
+
+/// Represents a source of Bible content in Unified Scripture XML (USX),
+/// where each book is a separate XML file, as in Digital Bible Library bundles.
+///
+/// USX has the same paragraph, character and note styles as USFM, e.g.
+/// `<para style="q2">`, `<char style="nd">` and `<note style="f">`, so the content is read
+/// the same way as from an equivalent [`crate::UsfmSource`].
+///
+/// Chapters and verses may be marked with start and end milestones
+/// (`<verse number="16" sid="JHN 3:16"/>` ... `<verse eid="JHN 3:16"/>`) as in USX 3,
+/// or with start milestones only as in USX 2, in which case a verse ends where the next one starts.
+///
+/// See https://ubsicap.github.io/usx/ for more information.
+pub struct UsxSource {
+    source: IndexedSource,
+}
+impl Source for UsxSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+}
+impl UsxSource {
+    /// Reads the book files with the extension `.usx` in a directory.
+    pub fn from_directory(path: &Path) -> Result<Self, SourceError> {
+        let entries = fs::read_dir(path).map_err(|error| SourceError::io(path, error))?;
+        let mut book_paths = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|error| SourceError::io(path, error))?;
+            let book_path = entry.path();
+            let is_usx = book_path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("usx"));
+            if is_usx {
+                book_paths.push(book_path);
+            }
+        }
+        // Sort for a deterministic result when a book is in many files.
+        book_paths.sort();
+
+        let books = book_paths
+            .iter()
+            .map(|book_path| fs::read(book_path).map_err(|error| SourceError::io(book_path, error)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_books(books)
+    }
+    /// Creates a source from the USX content of book files already in memory.
+    pub fn from_books(
+        books: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    ) -> Result<Self, SourceError> {
+        let books = books
+            .into_iter()
+            .map(|book| read_book(book.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            source: index_books(books),
+        })
+    }
+
+    /// Returns the parsed source, e.g. to find headings, Psalm titles and introductions.
+    pub fn as_index(&self) -> &IndexedSource {
+        &self.source
+    }
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+}
+
+/// Reads a USX book by converting its elements into the equivalent USFM markers.
+fn read_book(data: &[u8]) -> Result<BookContent, SourceError> {
+    let mut parser = xml::EventReader::new(Cursor::new(data));
+    let mut reader = BookReader::default();
+    // Styles of open `<char>`, `<note>` and `<figure>` elements with the attributes of words,
+    // which are closed by their end elements.
+    let mut open_styles: Vec<(String, String)> = Vec::new();
+
+    loop {
+        let event = parser.next()?;
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                let style = find_xml_attribute(&attributes, "style");
+                match name.local_name.as_str() {
+                    "book" => {
+                        let code = find_xml_attribute(&attributes, "code").unwrap_or_default();
+                        reader.handle_token(marker("id"));
+                        reader.handle_token(Token::Text(&format!("{code} ")));
+                    }
+                    "chapter" | "verse" => {
+                        let marker_name = if name.local_name == "chapter" {
+                            "c"
+                        } else {
+                            "v"
+                        };
+                        if let Some(number) = find_xml_attribute(&attributes, "number") {
+                            reader.handle_token(marker(marker_name));
+                            reader.handle_token(Token::Text(&format!("{number} ")));
+                        } else if marker_name == "v"
+                            && find_xml_attribute(&attributes, "eid").is_some()
+                        {
+                            reader.finish_verse();
+                        }
+                    }
+                    "para" | "row" => {
+                        reader.handle_token(marker(style.unwrap_or("p")));
+                    }
+                    "char" | "note" | "figure" => {
+                        let style = style.unwrap_or(match name.local_name.as_str() {
+                            "note" => "f",
+                            "figure" => "fig",
+                            _ => "",
+                        });
+                        reader.handle_token(marker(style));
+                        if let Some(caller) = find_xml_attribute(&attributes, "caller") {
+                            reader.handle_token(Token::Text(&format!("{caller} ")));
+                        }
+                        open_styles.push((style.to_string(), word_attributes(&attributes)));
+                    }
+                    // Other milestones than those with start and end, e.g. `<ms style="ts"/>`,
+                    // mark nothing in the text.
+                    "ms" => {
+                        let Some(style) =
+                            style.filter(|style| style.ends_with("-s") || style.ends_with("-e"))
+                        else {
+                            continue;
+                        };
+                        reader.handle_token(marker(style));
+                        let attributes = attributes
+                            .iter()
+                            .filter(|attribute| attribute.name.local_name != "style")
+                            .map(|attribute| {
+                                format!("{}=\"{}\"", attribute.name.local_name, attribute.value)
+                            })
+                            .collect::<Vec<_>>();
+                        reader.handle_token(Token::Text(&format!("|{}", attributes.join(" "))));
+                        reader.handle_token(Token::Marker {
+                            name: "",
+                            is_closing: true,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::EndElement { name } => match name.local_name.as_str() {
+                "para" | "row" => reader.end_paragraph(),
+                "char" | "note" | "figure" => {
+                    if let Some((style, word_attributes)) = open_styles.pop() {
+                        // Attributes of words come before the end marker, as in USFM.
+                        if !word_attributes.is_empty() {
+                            reader.handle_token(Token::Text(&word_attributes));
+                        }
+                        reader.handle_token(Token::Marker {
+                            name: &style,
+                            is_closing: true,
+                        });
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+                reader.handle_token(Token::Text(&text));
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    if !reader.has_book_code() {
+        return Err(SourceError::UnexpectedStructure(
+            "USX book has no book code (<book code>)".to_string(),
+        ));
+    }
+    reader.finish()
+}
+fn marker(name: &str) -> Token<'_> {
+    Token::Marker {
+        name,
+        is_closing: false,
+    }
+}
+/// Returns the word-level attributes of a `<char>` element in the USFM form `|strong="G2316"`,
+/// or an empty string if it has none.
+fn word_attributes(attributes: &[OwnedAttribute]) -> String {
+    let word_attributes = attributes
+        .iter()
+        .filter(|attribute| {
+            matches!(
+                attribute.name.local_name.as_str(),
+                "lemma" | "strong" | "x-morph"
+            )
+        })
+        .map(|attribute| format!("{}=\"{}\"", attribute.name.local_name, attribute.value))
+        .collect::<Vec<_>>();
+    if word_attributes.is_empty() {
+        String::new()
+    } else {
+        format!("|{}", word_attributes.join(" "))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_reference, Book, OsisSource, Segment, TextStyle};
+
+    const JOHN_USX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<usx version="3.0">
+  <book code="JHN" style="id">King James Version</book>
+  <para style="h">John</para>
+  <para style="mt1">The Gospel according to John</para>
+  <para style="ip">The Gospel according to <char style="bk">John</char>.</para>
+  <chapter number="3" style="c" sid="JHN 3"/>
+  <para style="s1">The new birth</para>
+  <para style="p"><verse number="3" style="v" sid="JHN 3:3"/>Jesus answered and said unto him, <ms style="qt-s" who="Jesus"/>Verily, verily, I say unto thee, Except a man be born again<note caller="+" style="f"><char style="fr" closed="false">3:3 </char><char style="ft" closed="false">Or, from above</char></note>, he cannot see the kingdom of God.<ms style="qt-e"/><verse eid="JHN 3:3"/></para>
+  <para style="p"><verse number="16" style="v" sid="JHN 3:16"/><ms style="qt-s" who="Jesus"/>For <char style="w" strong="G2316" x-morph="robinson:N-NSM">God</char> so loved<ms style="ts"/> the world<note caller="-" style="x"><char style="xo" closed="false">3:16 </char><char style="xt" closed="false"><ref loc="ROM 5:8">Rom. 5:8</ref></char></note>, that he gave his only begotten Son, that whosoever believeth in him should not perish, but have everlasting life.<ms style="qt-e"/><verse eid="JHN 3:16"/></para>
+  <chapter eid="JHN 3"/>
+</usx>"#;
+    const JOHN_OSIS: &str = r#"<osis><osisText>
+        <div type="book" osisID="John">
+          <title type="main">The Gospel according to John</title>
+          <div type="introduction"><p>The Gospel according to John.</p></div>
+          <chapter osisID="John.3">
+            <title>The new birth</title>
+            <p><verse osisID="John.3.3">Jesus answered and said unto him, <q who="Jesus">Verily, verily, I say unto thee, Except a man be born again<note>Or, from above</note>, he cannot see the kingdom of God.</q></verse></p>
+            <p><verse osisID="John.3.16"><q who="Jesus">For <w lemma="strong:G2316" morph="robinson:N-NSM">God</w> so loved the world<note type="crossReference"><reference osisRef="Rom.5.8">Rom. 5:8</reference></note>, that he gave his only begotten Son, that whosoever believeth in him should not perish, but have everlasting life.</q></verse></p>
+          </chapter>
+        </div>
+        </osisText></osis>"#;
+
+    #[test]
+    fn find_same_content_as_equivalent_osis() {
+        let usx_source = UsxSource::from_books([JOHN_USX]).unwrap();
+        let osis_source = OsisSource::from_data(JOHN_OSIS.into());
+        for reference in ["John 3", "John 3:16"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                usx_source.find_content(&parse_result).unwrap(),
+                osis_source.find_content(&parse_result).unwrap(),
+                "{reference}"
+            );
+            assert_eq!(
+                usx_source.as_index().find_headings(&parse_result),
+                osis_source.find_headings(&parse_result).unwrap(),
+                "{reference}"
+            );
+        }
+        assert_eq!(
+            usx_source.as_index().find_introduction(Book::John),
+            Some("The Gospel according to John.")
+        );
+    }
+    #[test]
+    fn read_verses_without_end_milestones() {
+        let usx_source = UsxSource::from_books([r#"<usx version="2.5">
+  <book code="PSA" style="id"/>
+  <chapter number="23" style="c"/>
+  <para style="d">A Psalm of David.</para>
+  <para style="q1"><verse number="1" style="v"/>The <char style="nd">LORD</char> is my shepherd;</para>
+  <para style="q2">I shall not want.</para>
+  <para style="q1"><verse number="2" style="v"/>He maketh me to lie down in green pastures:</para>
+  <para style="q1"><verse number="3-4" style="v"/>He restoreth my soul.</para>
+</usx>"#])
+        .unwrap();
+        let references = usx_source
+            .find_content(&parse_reference("Psalms 23").unwrap())
+            .unwrap();
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| reference.content.plain_text())
+                .collect::<Vec<_>>(),
+            [
+                "The LORD is my shepherd; I shall not want.",
+                "He maketh me to lie down in green pastures:",
+                // Combined verses have the same content under each verse number.
+                "He restoreth my soul.",
+                "He restoreth my soul."
+            ]
+        );
+        assert_eq!(
+            references[0].content.segments()[2],
+            Segment::Text {
+                text: "LORD".to_string(),
+                style: TextStyle {
+                    divine_name: true,
+                    ..TextStyle::default()
+                }
+            }
+        );
+        assert_eq!(
+            usx_source.as_index().find_psalm_title(23),
+            Some("A Psalm of David.")
+        );
+
+        assert!(UsxSource::from_books(["<usx><para>"]).is_err());
+        let error = UsxSource::from_books([r#"<usx><chapter number="1"/></usx>"#])
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected structure: USX book has no book code (<book code>)"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::fmt;
+
+use crate::{versification, Book};
+
+/// Compact identifier of a single verse, packed into an integer of the form `BBCCCVVV`,
+/// where `BB` is the book number, `CCC` the chapter and `VVV` the verse number.
+///
+/// For example, John 3:16 is `43003016`. The packing is lossless, and because the book is in the
+/// most significant digits, IDs sort in canonical order. This makes them suitable as database
+/// keys and for indexing sources.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VerseId(u32);
+impl VerseId {
+    pub fn new(book: Book, chapter: u8, verse: u8) -> Self {
+        Self(book.number() as u32 * 1_000_000 + chapter as u32 * 1_000 + verse as u32)
+    }
+    /// Parses a verse ID from an OSIS verse reference, e.g. "John.3.16".
+    pub fn from_osis_id(osis_id: &str) -> Option<Self> {
+        let mut parts = osis_id.split('.');
+        let book = Book::from_osis_id(parts.next()?)?;
+        let chapter = parts.next()?.parse::<u8>().ok()?;
+        let verse = parts.next()?.parse::<u8>().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self::new(book, chapter, verse))
+    }
+
+    pub fn book(self) -> Book {
+        // Book number is validated on construction, so this never fails.
+        Book::from_number((self.0 / 1_000_000) as u8).unwrap()
+    }
+    pub fn chapter(self) -> u8 {
+        (self.0 / 1_000 % 1_000) as u8
+    }
+    pub fn verse(self) -> u8 {
+        (self.0 % 1_000) as u8
+    }
+    /// Returns the ID of the verse that follows this one in canonical order,
+    /// moving on to the next chapter or book when necessary.
+    ///
+    /// Returns `None` after the last verse of the Bible or if this verse is outside the versification.
+    pub fn next(self) -> Option<Self> {
+        let (book, chapter, verse) = (self.book(), self.chapter(), self.verse());
+        if verse < versification::verse_count(book, chapter)? {
+            Some(Self::new(book, chapter, verse + 1))
+        } else if chapter < versification::chapter_count(book) {
+            Some(Self::new(book, chapter + 1, 1))
+        } else {
+            Book::from_number(book.number() + 1).map(|book| Self::new(book, 1, 1))
+        }
+    }
+}
+impl fmt::Display for VerseId {
+    /// Formats the verse ID as an OSIS verse reference, e.g. "John.3.16".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            self.book().osis_id(),
+            self.chapter(),
+            self.verse()
+        )
+    }
+}
+impl From<VerseId> for u32 {
+    fn from(id: VerseId) -> Self {
+        id.0
+    }
+}
+impl TryFrom<u32> for VerseId {
+    type Error = InvalidVerseId;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        let book = u8::try_from(value / 1_000_000)
+            .ok()
+            .and_then(Book::from_number)
+            .ok_or(InvalidVerseId(value))?;
+        let chapter = u8::try_from(value / 1_000 % 1_000).map_err(|_| InvalidVerseId(value))?;
+        let verse = u8::try_from(value % 1_000).map_err(|_| InvalidVerseId(value))?;
+        Ok(Self::new(book, chapter, verse))
+    }
+}
+
+/// Error for an integer that does not represent a valid verse ID.
+#[derive(Debug, PartialEq)]
+pub struct InvalidVerseId(pub u32);
+impl fmt::Display for InvalidVerseId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid verse ID: {}", self.0)
+    }
+}
+impl std::error::Error for InvalidVerseId {}
+
+/// Inclusive range of verses from a start verse to an end verse, possibly spanning chapters and books.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VerseIdRange {
+    start: VerseId,
+    end: VerseId,
+}
+impl VerseIdRange {
+    /// Creates a range from `start` to `end`, both inclusive.
+    /// Returns `None` if `end` comes before `start`.
+    pub fn new(start: VerseId, end: VerseId) -> Option<Self> {
+        if end < start {
+            return None;
+        }
+        Some(Self { start, end })
+    }
+    /// Parses a range from an OSIS reference, e.g. "John.3.16", "John.3.16-John.3.18",
+    /// "Ps.23" or "Jude". A reference to a chapter or a book covers all of its verses.
+    ///
+    /// A work prefix, e.g. "KJV:John.3.16", and a grain suffix, e.g. "John.3.16!a", are ignored.
+    pub fn from_osis_ref(osis_ref: &str) -> Option<Self> {
+        let osis_ref = osis_ref
+            .split_once(':')
+            .map_or(osis_ref, |(_, osis_ref)| osis_ref);
+        let (start, end) = osis_ref.split_once('-').unwrap_or((osis_ref, osis_ref));
+        Self::new(
+            Self::from_osis_ref_part(start)?.start,
+            Self::from_osis_ref_part(end)?.end,
+        )
+    }
+    /// Returns a range that covers the verse IDs of all books, chapters and verses.
+    pub(crate) fn all() -> Self {
+        Self {
+            start: VerseId::new(Book::Genesis, 1, 1),
+            end: VerseId::new(Book::Revelation, u8::MAX, u8::MAX),
+        }
+    }
+    /// Creates a range of a single verse.
+    pub fn single(id: VerseId) -> Self {
+        Self { start: id, end: id }
+    }
+
+    /// Parses a range of a single OSIS reference part, which is a verse, chapter or book.
+    fn from_osis_ref_part(part: &str) -> Option<Self> {
+        let part = part.split_once('!').map_or(part, |(part, _)| part);
+        let mut parts = part.split('.');
+        let book = Book::from_osis_id(parts.next()?)?;
+        let chapter = parts.next().map(str::parse::<u8>);
+        let verse = parts.next().map(str::parse::<u8>);
+        if parts.next().is_some() {
+            return None;
+        }
+        match (chapter, verse) {
+            (Some(chapter), Some(verse)) => {
+                Some(Self::single(VerseId::new(book, chapter.ok()?, verse.ok()?)))
+            }
+            (Some(chapter), None) => {
+                let chapter = chapter.ok()?;
+                Some(Self {
+                    start: VerseId::new(book, chapter, 1),
+                    end: VerseId::new(book, chapter, book.verse_count(chapter)?),
+                })
+            }
+            _ => {
+                let last_chapter = book.chapter_count();
+                Some(Self {
+                    start: VerseId::new(book, 1, 1),
+                    end: VerseId::new(book, last_chapter, book.verse_count(last_chapter)?),
+                })
+            }
+        }
+    }
+
+    pub fn start(&self) -> VerseId {
+        self.start
+    }
+    pub fn end(&self) -> VerseId {
+        self.end
+    }
+    pub fn contains(&self, id: VerseId) -> bool {
+        self.start <= id && id <= self.end
+    }
+    /// Returns whether the ranges have at least one verse ID in common.
+    pub fn overlaps(&self, other: &VerseIdRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+    /// Returns the number of verses in the range.
+    ///
+    /// The count is calculated from the verse counts of chapters instead of iterating each verse.
+    ///
+    /// Returns `None` if the range crosses chapters and the versification doesn't have
+    /// a chapter in the range, or the start verse is past the end of its chapter,
+    /// e.g. "John 3:40" to "John 4:1".
+    pub fn verse_count(&self) -> Option<usize> {
+        let (start, end) = (self.start, self.end);
+        if (start.book(), start.chapter()) == (end.book(), end.chapter()) {
+            return Some((end.verse() - start.verse()) as usize + 1);
+        }
+        if start.verse() > start.book().verse_count(start.chapter())? {
+            return None;
+        }
+
+        let mut count = 0;
+        let mut book = start.book();
+        let mut chapter = start.chapter();
+        // Count the verses of each full chapter before the end chapter.
+        while (book, chapter) != (end.book(), end.chapter()) {
+            count += book.verse_count(chapter)? as usize;
+            if chapter < book.chapter_count() {
+                chapter += 1;
+            } else {
+                // There is no next book if the end is past the last chapter of the Bible.
+                book = Book::from_number(book.number() + 1)?;
+                chapter = 1;
+            }
+        }
+        // Exclude the verses before the start verse in the start chapter.
+        Some(count + end.verse() as usize - (start.verse() as usize).saturating_sub(1))
+    }
+    /// Returns an iterator over each verse ID in the range, in canonical order.
+    pub fn iter(&self) -> VerseIdRangeIter {
+        VerseIdRangeIter {
+            next: Some(self.start),
+            end: self.end,
+        }
+    }
+}
+impl IntoIterator for VerseIdRange {
+    type Item = VerseId;
+    type IntoIter = VerseIdRangeIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over verse IDs in a [`VerseIdRange`].
+pub struct VerseIdRangeIter {
+    next: Option<VerseId>,
+    end: VerseId,
+}
+impl Iterator for VerseIdRangeIter {
+    type Item = VerseId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.filter(|id| *id <= self.end)?;
+        self.next = current.next();
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_verse_id_to_integer_and_back() {
+        let id = VerseId::new(Book::John, 3, 16);
+        assert_eq!(u32::from(id), 43_003_016);
+        assert_eq!(VerseId::try_from(43_003_016), Ok(id));
+        assert_eq!((id.book(), id.chapter(), id.verse()), (Book::John, 3, 16));
+
+        assert_eq!(
+            VerseId::try_from(67_001_001),
+            Err(InvalidVerseId(67_001_001))
+        );
+        assert_eq!(
+            VerseId::try_from(43_300_001),
+            Err(InvalidVerseId(43_300_001))
+        );
+    }
+    #[test]
+    fn convert_verse_id_to_osis_id_and_back() {
+        let id = VerseId::new(Book::FirstJohn, 4, 8);
+        assert_eq!(id.to_string(), "1John.4.8");
+        assert_eq!(VerseId::from_osis_id("1John.4.8"), Some(id));
+        assert_eq!(VerseId::from_osis_id("1John.4"), None);
+    }
+    #[test]
+    fn compare_verse_ids_in_canonical_order() {
+        assert!(VerseId::new(Book::Genesis, 50, 26) < VerseId::new(Book::Exodus, 1, 1));
+        assert!(VerseId::new(Book::John, 3, 16) < VerseId::new(Book::John, 10, 1));
+        assert!(VerseId::new(Book::John, 3, 9) < VerseId::new(Book::John, 3, 10));
+    }
+    #[test]
+    fn iterate_verse_id_range_across_chapters_and_books() {
+        let range = VerseIdRange::new(
+            VerseId::new(Book::Malachi, 4, 5),
+            VerseId::new(Book::Matthew, 1, 2),
+        )
+        .unwrap();
+        assert_eq!(
+            range.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            ["Mal.4.5", "Mal.4.6", "Matt.1.1", "Matt.1.2"]
+        );
+        assert!(range.contains(VerseId::new(Book::Malachi, 4, 6)));
+        assert!(!range.contains(VerseId::new(Book::Matthew, 1, 3)));
+        assert_eq!(range.verse_count(), Some(4));
+
+        assert_eq!(
+            VerseIdRange::new(
+                VerseId::new(Book::John, 3, 2),
+                VerseId::new(Book::John, 3, 1)
+            ),
+            None
+        );
+    }
+    #[test]
+    fn count_no_verses_of_range_outside_versification() {
+        let range = |start: (Book, u8, u8), end: (Book, u8, u8)| {
+            VerseIdRange::new(
+                VerseId::new(start.0, start.1, start.2),
+                VerseId::new(end.0, end.1, end.2),
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            range((Book::John, 3, 40), (Book::John, 4, 1)).verse_count(),
+            None
+        );
+        assert_eq!(
+            range((Book::Revelation, 22, 21), (Book::Revelation, 23, 1)).verse_count(),
+            None
+        );
+        assert_eq!(
+            range((Book::John, 3, 40), (Book::John, 3, 41)).verse_count(),
+            Some(2)
+        );
+    }
+    #[test]
+    fn parse_verse_id_range_from_osis_ref() {
+        let range = |start: (Book, u8, u8), end: (Book, u8, u8)| {
+            VerseIdRange::new(
+                VerseId::new(start.0, start.1, start.2),
+                VerseId::new(end.0, end.1, end.2),
+            )
+        };
+        assert_eq!(
+            VerseIdRange::from_osis_ref("John.3.16"),
+            range((Book::John, 3, 16), (Book::John, 3, 16))
+        );
+        assert_eq!(
+            VerseIdRange::from_osis_ref("KJV:John.3.16-John.4.2"),
+            range((Book::John, 3, 16), (Book::John, 4, 2))
+        );
+        assert_eq!(
+            VerseIdRange::from_osis_ref("Ps.23"),
+            range((Book::Psalms, 23, 1), (Book::Psalms, 23, 6))
+        );
+        assert_eq!(
+            VerseIdRange::from_osis_ref("Jude"),
+            range((Book::Jude, 1, 1), (Book::Jude, 1, 25))
+        );
+        assert_eq!(VerseIdRange::from_osis_ref("John.3.x"), None);
+        assert_eq!(VerseIdRange::from_osis_ref("John.4.2-John.3.16"), None);
+    }
+}
@@ -0,0 +1,643 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+};
+
+use xml::{attribute::OwnedAttribute, reader::XmlEvent};
+
+use crate::{
+    osis::{collapse_whitespace, find_xml_attribute, PendingHeading},
+    usfm::{parse_number, parse_verse_span},
+    Book, Break, Heading, HeadingType, IndexedSource, MorphologyCode, Note, NoteType, Reference,
+    ReferenceParseResult, Segment, Source, SourceError, SourceMetadata, StrongsNumber, Testament,
+    TextStyle, VerseContent, VerseId, VerseIdRange, VerseIndex, Word,
+};
+
+/// Represents a source of Bible content in Zefania XML, where books, chapters and verses are
+/// numbered elements (`<BIBLEBOOK bnumber="43">`, `<CHAPTER cnumber="3">`, `<VERS vnumber="16">`).
+///
+/// Books are identified by their numbers in canonical order, 1 being Genesis and 66 Revelation.
+/// Books with other numbers, e.g. of the Apocrypha, are skipped.
+///
+/// Within verses:
+/// - `<STYLE>` is read as a text style by its `fs` or `css` attribute, e.g. `fs="italic"` or
+///   `css="color:red"` for words of Christ.
+/// - `<gr str="2316" rmac="N-NSM">` is read as a [`Word`] segment. Strong's numbers without
+///   a language prefix are Hebrew in the Old Testament and Greek in the New Testament.
+/// - `<NOTE>` and `<XREF mscope="45;5;8">` are read as [`Note`] segments.
+/// - `<BR art="x-p"/>` and `<BR art="x-nl"/>` are read as [`Break`] segments.
+///
+/// Combined verses (`<VERS vnumber="1-2">`) have the same content under each of their
+/// verse numbers.
+///
+/// Captions (`<CAPTION>`) are read as headings and prologues (`<PROLOG>`) as book introductions.
+///
+/// Metadata is read from `<INFORMATION>`, with the `biblename` attribute of `<XMLBIBLE>`
+/// as the title if it has none.
+///
+/// The whole source is parsed when created.
+///
+/// See https://www.bgfdb.de/zefaniaxml/bml/ for more information.
+pub struct ZefaniaSource {
+    source: IndexedSource,
+}
+impl Source for ZefaniaSource {
+    fn find_content(
+        &self,
+        parse_result: &ReferenceParseResult,
+    ) -> Result<Vec<Reference>, SourceError> {
+        self.source.find_content(parse_result)
+    }
+    fn metadata(&self) -> &SourceMetadata {
+        self.source.metadata()
+    }
+}
+impl ZefaniaSource {
+    pub fn from_file(mut file: File) -> Result<Self, SourceError> {
+        let mut data = vec![];
+        file.read_to_end(&mut data)
+            .map_err(|error| SourceError::Io { path: None, error })?;
+        Self::from_data(&data)
+    }
+    /// Creates a source from Zefania XML content already in memory.
+    pub fn from_data(data: &[u8]) -> Result<Self, SourceError> {
+        let mut parser = xml::EventReader::new(Cursor::new(data));
+        let mut reader = ZefaniaReader::default();
+        loop {
+            let event = parser.next()?;
+            match event {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => reader.handle_start_element(&name.local_name.to_uppercase(), &attributes),
+                XmlEvent::EndElement { name } => {
+                    reader.handle_end_element(&name.local_name.to_uppercase())
+                }
+                XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+                    reader.handle_text(&text)
+                }
+                XmlEvent::EndDocument => break,
+                _ => {}
+            }
+        }
+        reader.finish_verse();
+        let mut metadata = reader.metadata;
+        if metadata.title.is_none() {
+            metadata.title = reader.bible_name;
+        }
+        Ok(Self {
+            source: IndexedSource::new(
+                VerseIndex::new(reader.verses),
+                reader.headings,
+                reader.introductions,
+            )
+            .with_metadata(metadata),
+        })
+    }
+
+    /// Returns the parsed source, e.g. to find headings, Psalm titles and introductions.
+    pub fn as_index(&self) -> &IndexedSource {
+        &self.source
+    }
+    /// Returns the parsed source, e.g. to compile it with [`crate::CompiledSource`].
+    pub fn into_index(self) -> IndexedSource {
+        self.source
+    }
+}
+
+/// Reads verses, captions, prologues and metadata from Zefania XML events.
+#[derive(Default)]
+struct ZefaniaReader {
+    /// Book whose `<BIBLEBOOK>` is currently open, or `None` if the book is not supported.
+    current_book: Option<Book>,
+    chapter: u8,
+    verses: Vec<(VerseId, VerseContent)>,
+    /// Verse currently being read and its content so far.
+    current_verse: Option<(VerseId, VerseContent)>,
+    /// Further verse IDs of the verse currently being read, if it combines verses.
+    combined_verse_ids: Vec<VerseId>,
+    /// Text style of each open element, the innermost last.
+    style_stack: Vec<TextStyle>,
+    /// Note currently being read and the depth of open elements within it.
+    current_note: Option<(Note, usize)>,
+    /// Tagged word currently being read.
+    current_word: Option<Word>,
+    /// Breaks read after the latest text. They come at the start of the next verse,
+    /// unless more text of the current verse follows.
+    pending_breaks: Vec<Break>,
+    headings: Vec<Heading>,
+    /// Headings read before the verse they stand before.
+    pending_headings: Vec<PendingHeading>,
+    /// Heading currently being read, the verse number it stands before if given,
+    /// and the depth of open elements within it.
+    current_heading: Option<(PendingHeading, Option<u8>, usize)>,
+    introductions: Vec<(Book, String)>,
+    /// Introduction text currently being read and the depth of open elements within it.
+    current_introduction: Option<(String, usize)>,
+    bible_name: Option<String>,
+    metadata: SourceMetadata,
+    in_information: bool,
+    /// Element of `<INFORMATION>` currently being read and its text so far.
+    current_information: Option<(String, String)>,
+}
+impl ZefaniaReader {
+    fn handle_start_element(&mut self, name: &str, attributes: &[OwnedAttribute]) {
+        let style = element_style(
+            self.style_stack.last().copied().unwrap_or_default(),
+            name,
+            attributes,
+        );
+        self.style_stack.push(style);
+
+        if self.in_information {
+            if self.current_information.is_none() {
+                self.current_information = Some((name.to_string(), String::new()));
+            }
+            return;
+        }
+        if let Some((note, depth)) = &mut self.current_note {
+            *depth += 1;
+            if name == "XREF" {
+                note.references.extend(parse_cross_references(attributes));
+            }
+            return;
+        }
+        if let Some((_, _, depth)) = &mut self.current_heading {
+            *depth += 1;
+            return;
+        }
+        if let Some((_, depth)) = &mut self.current_introduction {
+            *depth += 1;
+            return;
+        }
+
+        match name {
+            "XMLBIBLE" => {
+                self.bible_name = find_xml_attribute(attributes, "biblename").map(str::to_string)
+            }
+            "INFORMATION" => self.in_information = true,
+            "BIBLEBOOK" => {
+                self.finish_verse();
+                self.current_book = find_xml_attribute(attributes, "bnumber")
+                    .and_then(|number| number.parse().ok())
+                    .and_then(Book::from_number);
+                self.chapter = 0;
+            }
+            "CHAPTER" => {
+                self.finish_verse();
+                self.chapter = find_xml_attribute(attributes, "cnumber")
+                    .and_then(|number| number.parse().ok())
+                    .unwrap_or(0);
+            }
+            "VERS" => {
+                self.finish_verse();
+                let (number_from, number_to) = find_xml_attribute(attributes, "vnumber")
+                    .and_then(parse_verse_span)
+                    .unwrap_or((0, 0));
+                let Some(book) = self
+                    .current_book
+                    .filter(|_| self.chapter > 0 && number_from > 0)
+                else {
+                    return;
+                };
+                let id = VerseId::new(book, self.chapter, number_from);
+                self.combined_verse_ids = (number_from + 1..=number_to)
+                    .map(|number| VerseId::new(book, self.chapter, number))
+                    .collect();
+                self.headings.extend(
+                    std::mem::take(&mut self.pending_headings)
+                        .into_iter()
+                        .map(|heading| heading.into_heading(id)),
+                );
+                self.current_verse = Some((id, VerseContent::default()));
+            }
+            "CAPTION" => {
+                let heading = PendingHeading {
+                    heading_type: HeadingType::Section,
+                    canonical: false,
+                    text: String::new(),
+                };
+                let verse_number = find_xml_attribute(attributes, "vref").and_then(parse_number);
+                self.current_heading = Some((heading, verse_number, 0));
+            }
+            "PROLOG" => self.current_introduction = Some((String::new(), 0)),
+            "NOTE" | "REMARK" => {
+                let note_type = match (name, find_xml_attribute(attributes, "type")) {
+                    ("REMARK", _) | (_, Some("x-studynote")) => Some(NoteType::Study),
+                    (_, Some("x-crossref")) => Some(NoteType::CrossReference),
+                    (_, Some("x-footnote")) | (_, None) => None,
+                    (_, Some(note_type)) => Some(NoteType::Other(note_type.to_string())),
+                };
+                let note = Note {
+                    note_type,
+                    text: String::new(),
+                    references: Vec::new(),
+                };
+                self.current_note = Some((note, 0));
+            }
+            // Cross-references outside notes are notes of their own,
+            // with the reference as their text if they have none.
+            "XREF" => {
+                let note = Note {
+                    note_type: Some(NoteType::CrossReference),
+                    text: find_xml_attribute(attributes, "fscope")
+                        .unwrap_or_default()
+                        .to_string(),
+                    references: parse_cross_references(attributes),
+                };
+                self.current_note = Some((note, 0));
+            }
+            "GR" | "GRAM" => {
+                let testament = self.current_book.map(Book::testament);
+                self.current_word = Some(Word {
+                    text: String::new(),
+                    style: self.current_style(),
+                    strongs: find_xml_attribute(attributes, "str")
+                        .map(|strongs| parse_strongs_numbers(strongs, testament))
+                        .unwrap_or_default(),
+                    morphology: find_xml_attribute(attributes, "rmac")
+                        .map(|code| MorphologyCode {
+                            scheme: Some("robinson".to_string()),
+                            code: code.to_string(),
+                        })
+                        .into_iter()
+                        .collect(),
+                });
+            }
+            "BR" => match find_xml_attribute(attributes, "art") {
+                Some("x-p") => self.pending_breaks.push(Break::Paragraph),
+                _ => self.pending_breaks.push(Break::Line { indent: 0 }),
+            },
+            _ => {}
+        }
+    }
+    fn handle_end_element(&mut self, name: &str) {
+        self.style_stack.pop();
+
+        if self.in_information {
+            match self.current_information.take() {
+                Some((field_name, text)) if field_name == name => {
+                    set_information_field(&mut self.metadata, name, collapse_whitespace(&text))
+                }
+                Some(field) => self.current_information = Some(field),
+                None => self.in_information = false,
+            }
+            return;
+        }
+        if let Some((note, depth)) = &mut self.current_note {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            note.text = collapse_whitespace(&note.text);
+            let Some((note, _)) = self.current_note.take() else {
+                return;
+            };
+            if let Some(content) = self.current_content() {
+                content.push(Segment::Note(note));
+            }
+            return;
+        }
+        if let Some((_, _, depth)) = &mut self.current_heading {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            let Some((mut heading, verse_number, _)) = self.current_heading.take() else {
+                return;
+            };
+            heading.text = collapse_whitespace(&heading.text);
+            if heading.text.is_empty() {
+                return;
+            }
+            let book = self.current_book.filter(|_| self.chapter > 0);
+            match (book, verse_number) {
+                (Some(book), Some(verse_number)) => self
+                    .headings
+                    .push(heading.into_heading(VerseId::new(book, self.chapter, verse_number))),
+                (Some(_), None) => self.pending_headings.push(heading),
+                (None, _) => {}
+            }
+            return;
+        }
+        if let Some((_, depth)) = &mut self.current_introduction {
+            if *depth > 0 {
+                *depth -= 1;
+                return;
+            }
+            let Some((text, _)) = self.current_introduction.take() else {
+                return;
+            };
+            let text = collapse_whitespace(&text);
+            if let (Some(book), false) = (self.current_book, text.is_empty()) {
+                match self
+                    .introductions
+                    .iter_mut()
+                    .find(|(introduction_book, _)| *introduction_book == book)
+                {
+                    Some((_, introduction)) => {
+                        introduction.push(' ');
+                        introduction.push_str(&text);
+                    }
+                    None => self.introductions.push((book, text)),
+                }
+            }
+            return;
+        }
+
+        match name {
+            "VERS" => self.finish_verse(),
+            "GR" | "GRAM" => {
+                if let Some(mut word) = self.current_word.take() {
+                    word.text = collapse_whitespace(&word.text);
+                    if let Some(content) = self.current_content() {
+                        content.push(Segment::Word(word));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    fn handle_text(&mut self, text: &str) {
+        if let Some((_, information)) = &mut self.current_information {
+            information.push_str(text);
+            return;
+        }
+        if let Some((note, _)) = &mut self.current_note {
+            note.text.push_str(text);
+            return;
+        }
+        if let Some((heading, _, _)) = &mut self.current_heading {
+            heading.text.push_str(text);
+            return;
+        }
+        if let Some((introduction, _)) = &mut self.current_introduction {
+            introduction.push_str(text);
+            return;
+        }
+        if let Some(word) = &mut self.current_word {
+            word.text.push_str(text);
+            return;
+        }
+        let style = self.current_style();
+        // Whitespace alone doesn't continue a verse after a break.
+        let content = if text.trim().is_empty() {
+            self.current_verse.as_mut().map(|(_, content)| content)
+        } else {
+            self.current_content()
+        };
+        if let Some(content) = content {
+            content.push_text(text, style);
+        }
+    }
+    /// Returns the content of the verse currently being read,
+    /// after adding the breaks that turned out to be within the verse.
+    fn current_content(&mut self) -> Option<&mut VerseContent> {
+        let (_, content) = self.current_verse.as_mut()?;
+        for layout_break in self.pending_breaks.drain(..) {
+            content.push(Segment::Break(layout_break));
+            // Separate text on different lines and paragraphs also in plain text.
+            content.push_text(" ", TextStyle::default());
+        }
+        Some(content)
+    }
+    /// Returns the style of text at the current position.
+    fn current_style(&self) -> TextStyle {
+        self.style_stack.last().copied().unwrap_or_default()
+    }
+    /// Adds the verse currently being read, if any, to the verses read.
+    fn finish_verse(&mut self) {
+        if let Some((id, mut content)) = self.current_verse.take() {
+            content.collapse_whitespace();
+            let ids = std::iter::once(id).chain(std::mem::take(&mut self.combined_verse_ids));
+            self.verses.extend(ids.map(|id| (id, content.clone())));
+        }
+    }
+}
+
+/// Sets a field of metadata by an element of `<INFORMATION>`, unless an earlier element has set it.
+fn set_information_field(metadata: &mut SourceMetadata, name: &str, value: String) {
+    let field = match name {
+        "TITLE" => &mut metadata.title,
+        "LANGUAGE" => &mut metadata.language,
+        "PUBLISHER" => &mut metadata.publisher,
+        "RIGHTS" => &mut metadata.rights,
+        "IDENTIFIER" => &mut metadata.identifier,
+        _ => return,
+    };
+    if field.is_none() && !value.is_empty() {
+        *field = Some(value);
+    }
+}
+/// Returns the text style within an element, given the style of its parent element.
+fn element_style(parent_style: TextStyle, name: &str, attributes: &[OwnedAttribute]) -> TextStyle {
+    let mut style = parent_style;
+    if name != "STYLE" {
+        return style;
+    }
+    match find_xml_attribute(attributes, "fs") {
+        Some("italic") => style.italic = true,
+        Some("divineName") => style.divine_name = true,
+        _ => {}
+    }
+    let css = find_xml_attribute(attributes, "css")
+        .unwrap_or_default()
+        .to_lowercase()
+        .replace(char::is_whitespace, "");
+    if css.contains("font-style:italic") {
+        style.italic = true;
+    }
+    if ["color:red", "color:#ff0000", "color:#f00"]
+        .iter()
+        .any(|red| css.contains(red))
+    {
+        style.words_of_christ = true;
+    }
+    style
+}
+/// Parses the verses of the `mscope` attribute of a cross-reference, which lists references
+/// separated by spaces, each of the form "book;chapter;verse" or "book;chapter;verse-verse".
+fn parse_cross_references(attributes: &[OwnedAttribute]) -> Vec<VerseIdRange> {
+    find_xml_attribute(attributes, "mscope")
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|scope| {
+            let mut parts = scope.split(';');
+            let book = Book::from_number(parts.next()?.parse().ok()?)?;
+            let chapter = parts.next()?.parse().ok()?;
+            let verses = parts.next()?;
+            let (verse_from, verse_to) = verses.split_once('-').unwrap_or((verses, verses));
+            VerseIdRange::new(
+                VerseId::new(book, chapter, parse_number(verse_from)?),
+                VerseId::new(book, chapter, parse_number(verse_to)?),
+            )
+        })
+        .collect()
+}
+/// Parses Strong's numbers of a `str` attribute, which may list many numbers separated by spaces.
+/// Numbers without a language prefix are in the language of the given testament.
+fn parse_strongs_numbers(value: &str, testament: Option<Testament>) -> Vec<StrongsNumber> {
+    value
+        .split_whitespace()
+        .filter_map(|strongs| match strongs.parse::<u16>() {
+            Ok(number) => match testament? {
+                Testament::Old => Some(StrongsNumber::Hebrew(number)),
+                Testament::New => Some(StrongsNumber::Greek(number)),
+            },
+            Err(_) => StrongsNumber::parse(strongs),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_reference, OsisSource};
+
+    const ZEFANIA: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<XMLBIBLE biblename="King James Version">
+  <INFORMATION>
+    <language>en</language>
+    <rights>Public Domain</rights>
+  </INFORMATION>
+  <BIBLEBOOK bnumber="19" bname="Psalms">
+    <CHAPTER cnumber="23">
+      <CAPTION vref="1">The Lord is my shepherd</CAPTION>
+      <VERS vnumber="1">The <STYLE fs="divineName">LORD</STYLE> <STYLE fs="italic">is</STYLE> my shepherd;<BR art="x-nl"/> I shall not want.</VERS>
+      <VERS vnumber="2">He maketh me to lie down in green pastures:</VERS>
+    </CHAPTER>
+  </BIBLEBOOK>
+  <BIBLEBOOK bnumber="43" bname="John">
+    <CHAPTER cnumber="3">
+      <PROLOG>Nicodemus comes to Jesus by night.</PROLOG>
+      <VERS vnumber="16">For <gr str="G2316" rmac="N-NSM">God</gr> so loved the world<NOTE type="x-studynote">The love of God is universal.</NOTE>, that he gave his only begotten Son.</VERS>
+      <VERS vnumber="17"><BR art="x-p"/>For God sent not his Son into the world to condemn the world.</VERS>
+    </CHAPTER>
+  </BIBLEBOOK>
+</XMLBIBLE>"#;
+    const EQUIVALENT_OSIS: &str = r#"<osis><osisText>
+        <div type="book" osisID="Ps">
+          <chapter osisID="Ps.23">
+            <title>The Lord is my shepherd</title>
+            <verse osisID="Ps.23.1">The <divineName>LORD</divineName> <hi type="italic">is</hi> my shepherd;<lb/> I shall not want.</verse>
+            <verse osisID="Ps.23.2">He maketh me to lie down in green pastures:</verse>
+          </chapter>
+        </div>
+        <div type="book" osisID="John">
+          <div type="introduction">Nicodemus comes to Jesus by night.</div>
+          <chapter osisID="John.3">
+            <verse osisID="John.3.16">For <w lemma="strong:G2316" morph="robinson:N-NSM">God</w> so loved the world<note type="study">The love of God is universal.</note>, that he gave his only begotten Son.</verse>
+            <p><verse osisID="John.3.17">For God sent not his Son into the world to condemn the world.</verse></p>
+          </chapter>
+        </div>
+        </osisText></osis>"#;
+
+    #[test]
+    fn find_same_content_as_equivalent_osis() {
+        let zefania_source = ZefaniaSource::from_data(ZEFANIA.as_bytes()).unwrap();
+        let osis_source = OsisSource::from_data(EQUIVALENT_OSIS.into());
+        for reference in ["Psalms 23", "Psalms 23:1-2", "John 3", "John 3:16-18"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                zefania_source
+                    .find_content(&parse_result)
+                    .map_err(|err| err.to_string()),
+                osis_source
+                    .find_content(&parse_result)
+                    .map_err(|err| err.to_string()),
+                "{reference}"
+            );
+            assert_eq!(
+                zefania_source.as_index().find_headings(&parse_result),
+                osis_source.find_headings(&parse_result).unwrap(),
+                "{reference}"
+            );
+        }
+        assert_eq!(
+            zefania_source.as_index().find_introduction(Book::John),
+            osis_source
+                .find_introduction(Book::John)
+                .unwrap()
+                .as_deref()
+        );
+
+        let metadata = zefania_source.metadata();
+        assert_eq!(metadata.title.as_deref(), Some("King James Version"));
+        assert_eq!(metadata.language.as_deref(), Some("en"));
+        assert_eq!(metadata.rights.as_deref(), Some("Public Domain"));
+    }
+    #[test]
+    fn find_combined_verses_like_equivalent_osis() {
+        let zefania_source = ZefaniaSource::from_data(
+            br#"<XMLBIBLE><BIBLEBOOK bnumber="43"><CHAPTER cnumber="4">
+            <VERS vnumber="1-2">When therefore the Lord knew, (Though Jesus himself baptized not,)</VERS>
+            <VERS vnumber="3">He left Judaea.</VERS>
+            </CHAPTER></BIBLEBOOK></XMLBIBLE>"#,
+        )
+        .unwrap();
+        let osis_source = OsisSource::from_data(
+            r#"<osis><osisText><div type="book" osisID="John"><chapter osisID="John.4">
+            <verse osisID="John.4.1 John.4.2">When therefore the Lord knew, (Though Jesus himself baptized not,)</verse>
+            <verse osisID="John.4.3">He left Judaea.</verse>
+            </chapter></div></osisText></osis>"#
+                .into(),
+        );
+        for reference in ["John 4:1", "John 4:2", "John 4:1-3"] {
+            let parse_result = parse_reference(reference).unwrap();
+            assert_eq!(
+                zefania_source.find_content(&parse_result).unwrap(),
+                osis_source.find_content(&parse_result).unwrap(),
+                "{reference}"
+            );
+        }
+        assert_eq!(
+            zefania_source
+                .find_content(&parse_reference("John 4:1-3").unwrap())
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+    #[test]
+    fn read_red_letters_strongs_numbers_and_cross_references() {
+        let zefania_source = ZefaniaSource::from_data(
+            br#"<XMLBIBLE>
+  <BIBLEBOOK bnumber="1"><CHAPTER cnumber="1">
+    <VERS vnumber="1">In the beginning <gr str="430">God</gr> created the heaven and the earth.</VERS>
+  </CHAPTER></BIBLEBOOK>
+  <BIBLEBOOK bnumber="40"><CHAPTER cnumber="5">
+    <VERS vnumber="3"><STYLE css="color: #FF0000">Blessed are the poor in spirit</STYLE><XREF mscope="42;6;20-21" fscope="Luke 6:20-21"/>.</VERS>
+  </CHAPTER></BIBLEBOOK>
+  <BIBLEBOOK bnumber="67"><CHAPTER cnumber="1"><VERS vnumber="1">Apocrypha</VERS></CHAPTER></BIBLEBOOK>
+</XMLBIBLE>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            zefania_source
+                .as_index()
+                .find_verses_with_strongs(StrongsNumber::Hebrew(430))
+                .len(),
+            1
+        );
+
+        let references = zefania_source
+            .find_content(&parse_reference("Matthew 5:3").unwrap())
+            .unwrap();
+        assert_eq!(
+            references[0].content.words_of_christ(),
+            ["Blessed are the poor in spirit"]
+        );
+        let notes = references[0].content.notes();
+        assert_eq!(notes[0].1.note_type, Some(NoteType::CrossReference));
+        assert_eq!(notes[0].1.text, "Luke 6:20-21");
+        assert_eq!(
+            notes[0].1.references,
+            [VerseIdRange::new(
+                VerseId::new(Book::Luke, 6, 20),
+                VerseId::new(Book::Luke, 6, 21)
+            )
+            .unwrap()]
+        );
+
+        assert!(ZefaniaSource::from_data(b"<XMLBIBLE><BIBLEBOOK>").is_err());
+    }
+}